@@ -0,0 +1,102 @@
+use futures_core::Stream;
+use futures_util::{lock::Mutex, stream::StreamExt};
+use js_sys::{AsyncIterator, Object, Promise, Reflect};
+use std::{pin::Pin, rc::Rc};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+#[wasm_bindgen(inline_js = r#"
+export function createAsyncIterator(next, return_, throw_) {
+    return {
+        next,
+        return: return_,
+        throw: throw_,
+        [Symbol.asyncIterator]() {
+            return this;
+        },
+    };
+}
+"#)]
+extern {
+    #[wasm_bindgen(js_name = createAsyncIterator)]
+    fn create_async_iterator(next: &JsValue, return_: &JsValue, throw_: &JsValue) -> AsyncIterator;
+}
+
+type BoxStream = Pin<Box<dyn Stream<Item = Result<JsValue, JsValue>>>>;
+
+fn iterator_result(value: &JsValue, done: bool) -> Result<JsValue, JsValue> {
+    let result = Object::new();
+    Reflect::set(&result, &"value".into(), value)?;
+    Reflect::set(&result, &"done".into(), &done.into())?;
+    Ok(result.into())
+}
+
+/// Expose a Rust [`Stream`] to JS as an async iterator, which is also async iterable and so can be
+/// consumed with `for await`.
+///
+/// Each `Ok` item resolves a `next()` call, while an `Err` item rejects it and ends the iterator.
+/// Calls to `next()`, `return()`, and `throw()` are served in order. The stream is dropped once it
+/// is exhausted, fails, or when `return()` (e.g., from a `break` out of `for await`) or `throw()`
+/// is called.
+///
+/// The `next()`, `return()`, and `throw()` closures are only freed by the JS garbage collector
+/// when wasm-bindgen's weak reference support (`WASM_BINDGEN_WEAKREF`) is enabled, and are leaked
+/// otherwise. They let go of the stream at any of the points above, but an iterator which JS
+/// abandons before then keeps the stream alive along with them.
+pub fn stream_to_async_iterator<S>(stream: S) -> AsyncIterator
+where
+    S: Stream<Item = Result<JsValue, JsValue>> + 'static,
+{
+    let state: Rc<Mutex<Option<BoxStream>>> = Rc::new(Mutex::new(Some(Box::pin(stream))));
+
+    let next = {
+        let state = state.clone();
+        Closure::wrap(Box::new(move || {
+            let state = state.clone();
+            future_to_promise(async move {
+                let mut stream = state.lock().await;
+                let item = match stream.as_mut() {
+                    Some(inner) => inner.next().await,
+                    None => None,
+                };
+                match item {
+                    Some(Ok(value)) => iterator_result(&value, false),
+                    Some(Err(error)) => {
+                        *stream = None;
+                        Err(error)
+                    },
+                    None => {
+                        *stream = None;
+                        iterator_result(&JsValue::UNDEFINED, true)
+                    },
+                }
+            })
+        }) as Box<dyn FnMut() -> Promise>)
+        .into_js_value()
+    };
+
+    let return_ = {
+        let state = state.clone();
+        Closure::wrap(Box::new(move |value: JsValue| {
+            let state = state.clone();
+            future_to_promise(async move {
+                state.lock().await.take();
+                iterator_result(&value, true)
+            })
+        }) as Box<dyn FnMut(JsValue) -> Promise>)
+        .into_js_value()
+    };
+
+    let throw_ = {
+        Closure::wrap(Box::new(move |error: JsValue| {
+            let state = state.clone();
+            future_to_promise(async move {
+                state.lock().await.take();
+                Err(error)
+            })
+        }) as Box<dyn FnMut(JsValue) -> Promise>)
+        .into_js_value()
+    };
+
+    create_async_iterator(&next, &return_, &throw_)
+}
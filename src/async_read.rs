@@ -1,88 +1,280 @@
+use crate::{
+    convert::{self, Chunk},
+    error::ErrorClassifier,
+    source::Source,
+    sys,
+    JsStreamError,
+    StringEncoding,
+};
 use bytes::BufMut;
 use futures_core::Future;
-use futures_util::io::{self, AsyncBufRead, Cursor};
-use js_sys::{AsyncIterator, IteratorNext, JsString, Uint8Array};
+use futures_util::io;
+use js_sys::{AsyncIterator, IteratorNext, Uint8Array};
 use std::{
     convert::TryFrom,
+    io::{BufRead, Cursor},
     pin::Pin,
+    rc::Rc,
     task::{Context, Poll},
 };
 use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_futures::JsFuture;
 
 pub struct JsAsyncRead {
-    inner: AsyncIterator,
-    next: JsFuture,
+    inner: Source,
+    /// The pending result of the source, which is absent until the next chunk is requested.
+    next: Option<JsFuture>,
+    /// Whether the reader has reached EOF or failed.
+    done: bool,
+    /// Unread bytes of the current chunk which are still held in JS memory.
+    view: Option<Uint8Array>,
+    /// Unread bytes of the current chunk which have been copied into wasm memory.
     data: Cursor<Vec<u8>>,
+    /// Pending bytes of a [`Blob`](sys::Blob) chunk.
+    blob: Option<JsFuture>,
+    encoding: StringEncoding,
+    classifier: ErrorClassifier,
 }
 
 impl JsAsyncRead {
     /// The inner [`js_sys::AsyncIterator`] is expected to yield values of type
-    /// [`js_sys::JsString`] or [`js_sys::Uint8Array`].
+    /// [`js_sys::JsString`], [`js_sys::ArrayBuffer`], any [`js_sys::ArrayBuffer`] view (typed
+    /// arrays, [`js_sys::DataView`], Node `Buffer`), or `Blob`.
     pub fn new(inner: AsyncIterator) -> Result<Self, JsValue> {
-        let next = JsFuture::from(inner.next()?);
+        Self::with_source(Source::from_async_iterator(inner))
+    }
+
+    /// The iterable is iterated the way `for await` iterates it: through `[Symbol.asyncIterator]()`
+    /// or, failing that, `[Symbol.iterator]()`, e.g., a Node readable, a `ReadableStream` in newer
+    /// runtimes, or an array. It is expected to yield the same chunk types as [`JsAsyncRead::new`].
+    pub fn from_async_iterable(iterable: &JsValue) -> Result<Self, JsValue> {
+        Self::with_source(Source::from_async_iterable(iterable)?)
+    }
+
+    /// The inner [`js_sys::Iterator`] is stepped the way `for await` steps a sync iterable, so it
+    /// may yield promises, which are awaited before their values are read as chunks of the same
+    /// types as [`JsAsyncRead::new`].
+    pub fn from_sync_iterator(inner: js_sys::Iterator) -> Result<Self, JsValue> {
+        Self::with_source(Source::from_sync_iterator(inner))
+    }
+
+    /// The [`ReadableStream`](sys::ReadableStream) is read through a default reader, which is
+    /// acquired here and released on drop. It is expected to yield the same chunk types as
+    /// [`JsAsyncRead::new`].
+    pub fn from_readable_stream(stream: &sys::ReadableStream) -> Result<Self, JsValue> {
+        Self::with_source(Source::from_readable_stream(stream)?)
+    }
+
+    /// The [`ReadableStream`](sys::ReadableStream) must be a byte stream (`type: "bytes"`). It is
+    /// read through a `ReadableStreamBYOBReader` into a reusable JS buffer of `buffer_size` bytes,
+    /// so only the filled part of each read is copied into wasm memory and no chunks are
+    /// allocated per read. A `buffer_size` of `0` is treated as `1`. The reader is acquired here
+    /// and released on drop.
+    pub fn from_byte_stream(stream: &sys::ReadableStream, buffer_size: u32) -> Result<Self, JsValue> {
+        Self::with_source(Source::from_byte_stream(stream, buffer_size)?)
+    }
+
+    /// Cancel the source before it is exhausted and wait for it to clean up, by calling `return()`
+    /// on an async iterator or `cancel()` on a stream reader. Dropping calls `return()` on an async
+    /// iterator without waiting, and only releases a stream reader.
+    pub async fn cancel(mut self) -> Result<(), JsValue> {
+        if let Some(promise) = self.inner.cancel()? {
+            JsFuture::from(promise).await?;
+        }
+        Ok(())
+    }
+
+    fn with_source(mut inner: Source) -> Result<Self, JsValue> {
+        let next = Some(JsFuture::from(inner.next()?));
+        let done = false;
+        let view = None;
         let data = Default::default();
-        Ok(Self { inner, next, data })
+        let blob = None;
+        let encoding = Default::default();
+        let classifier = Rc::new(JsStreamError::io_error_kind);
+        Ok(Self {
+            inner,
+            next,
+            done,
+            view,
+            data,
+            blob,
+            encoding,
+            classifier,
+        })
     }
 
-    fn poll_read(
-        self: Pin<&mut Self>,
-        cx: &mut Context,
-        mut buf: &mut [u8],
-    ) -> Result<Poll<std::io::Result<usize>>, JsValue> {
-        let this = self.get_mut();
+    /// Set how [`js_sys::JsString`] chunks are converted into bytes. Defaults to
+    /// [`StringEncoding::Utf8`].
+    pub fn with_string_encoding(mut self, encoding: StringEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
 
-        let inner_buf = match Pin::new(&mut this.data).poll_fill_buf(cx) {
-            Poll::Ready(Ok(buf)) => buf,
-            Poll::Ready(Err(err)) => return Ok(Poll::Ready(Err(err))),
-            Poll::Pending => return Ok(Poll::Pending),
-        };
-
-        if inner_buf.is_empty() {
-            let next = Pin::new(&mut this.next);
-            let status = next.poll(cx)?;
-            match status {
-                Poll::Ready(object) => {
-                    let iterator_next = object.unchecked_into::<IteratorNext>();
-                    if iterator_next.done() {
-                        Ok(Poll::Ready(Ok(0)))
-                    } else {
-                        let value = {
-                            let next_value = iterator_next.value();
-                            if Uint8Array::instanceof(&next_value) {
-                                Ok(next_value.unchecked_into::<Uint8Array>().to_vec())
-                            } else if next_value.is_string() {
-                                if let Some(string) = next_value.unchecked_into::<JsString>().as_string() {
-                                    Ok(string.into_bytes())
-                                } else {
-                                    Err(js_sys::Error::new("Error converting JsString to String"))
-                                }
-                            } else {
-                                Err(js_sys::Error::new(
-                                    "Inner AsyncIterator must produce a JsString or Uint8Array",
-                                ))
-                            }
-                        }?;
-                        this.data = Cursor::new(value);
-                        match this.inner.next() {
-                            Ok(promise) => {
-                                this.next = JsFuture::from(promise);
-                            },
-                            Err(error) => {
-                                return Err(error);
-                            },
-                        }
-                        cx.waker().wake_by_ref();
-                        Ok(Poll::Pending)
-                    }
+    /// Set how errors are classified when surfaced through [`std::io::Error`]. Defaults to
+    /// [`JsStreamError::io_error_kind`].
+    pub fn with_error_classifier<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&JsStreamError) -> io::ErrorKind + 'static,
+    {
+        self.classifier = Rc::new(classifier);
+        self
+    }
+
+    fn data_remaining(&self) -> usize {
+        self.data.get_ref().len() - self.data.position() as usize
+    }
+
+    /// Store a chunk yielded by the inner iterator in `view`, `data`, or `blob`.
+    fn load_chunk(&mut self, value: JsValue) -> Result<(), JsStreamError> {
+        if self.inner.copy_filled(&value, self.data.get_mut()) {
+            self.data.set_position(0);
+            return Ok(());
+        }
+        match convert::classify_chunk(value)? {
+            Chunk::Bytes(view) => self.load_view(view),
+            Chunk::String(string) => self.data = Cursor::new(self.encoding.encode(&string)?),
+            Chunk::Blob(blob) => self.blob = Some(JsFuture::from(blob.array_buffer())),
+        }
+        Ok(())
+    }
+
+    fn load_view(&mut self, view: Uint8Array) {
+        if view.length() > 0 {
+            self.view = Some(view);
+        }
+    }
+
+    /// Poll the inner iterator for the next chunk, storing it in `view`, `data`, or `blob`.
+    /// Resolves to `false` once the inner iterator is exhausted, and after any error.
+    fn poll_chunk(&mut self, cx: &mut Context) -> Result<Poll<bool>, JsStreamError> {
+        let result = self.poll_chunk_inner(cx);
+        if result.is_err() {
+            // NOTE: a failed source is finished, but one which produced a bad chunk is left to be
+            // cancelled on drop
+            self.next = None;
+            self.blob = None;
+            self.done = true;
+        }
+        result
+    }
+
+    fn request_next(&mut self) -> Result<(), JsStreamError> {
+        match self.inner.next() {
+            Ok(promise) => {
+                self.next = Some(JsFuture::from(promise));
+                Ok(())
+            },
+            Err(error) => {
+                self.inner.finish();
+                Err(error.into())
+            },
+        }
+    }
+
+    fn poll_chunk_inner(&mut self, cx: &mut Context) -> Result<Poll<bool>, JsStreamError> {
+        if let Some(blob) = self.blob.as_mut() {
+            return match Pin::new(blob).poll(cx)? {
+                Poll::Ready(buffer) => {
+                    self.blob = None;
+                    self.load_view(Uint8Array::new(&buffer));
+                    Ok(Poll::Ready(true))
                 },
                 Poll::Pending => Ok(Poll::Pending),
+            };
+        }
+
+        if self.done {
+            return Ok(Poll::Ready(false));
+        }
+        if self.next.is_none() {
+            self.request_next()?;
+        }
+        let next = Pin::new(self.next.as_mut().unwrap());
+        match next.poll(cx) {
+            Poll::Ready(Err(error)) => {
+                self.inner.finish();
+                Err(error.into())
+            },
+            Poll::Ready(Ok(object)) => {
+                let iterator_next = object.unchecked_into::<IteratorNext>();
+                self.next = None;
+                if iterator_next.done() {
+                    self.done = true;
+                    self.inner.finish();
+                    Ok(Poll::Ready(false))
+                } else {
+                    self.load_chunk(iterator_next.value())?;
+                    // NOTE: requesting the next chunk lets the producer run up to its next `yield`,
+                    // where it may refill the buffer behind `view`, so that waits until the view
+                    // has been read
+                    if self.view.is_none() {
+                        self.request_next()?;
+                    }
+                    Ok(Poll::Ready(true))
+                }
+            },
+            Poll::Pending => Ok(Poll::Pending),
+        }
+    }
+
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Result<Poll<&[u8]>, JsStreamError> {
+        let this = self.get_mut();
+
+        // NOTE: loops so that a freshly loaded chunk is returned within the same poll; an empty
+        // chunk or a pending blob loops back around to poll again
+        while this.data_remaining() == 0 {
+            if let Some(view) = this.view.take() {
+                // Move the rest of the chunk into wasm memory, reusing the existing allocation.
+                let data = this.data.get_mut();
+                data.clear();
+                data.resize(view.length() as usize, 0);
+                view.copy_to(data);
+                this.data.set_position(0);
+            } else {
+                match this.poll_chunk(cx)? {
+                    Poll::Ready(true) => {},
+                    Poll::Ready(false) => return Ok(Poll::Ready(&[])),
+                    Poll::Pending => return Ok(Poll::Pending),
+                }
+            }
+        }
+
+        let position = this.data.position() as usize;
+        Ok(Poll::Ready(&this.data.get_ref()[position ..]))
+    }
+
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, mut buf: &mut [u8]) -> Result<Poll<usize>, JsStreamError> {
+        let this = self.get_mut();
+
+        // NOTE: loops so that a freshly loaded chunk is copied out within the same poll
+        loop {
+            if this.data_remaining() > 0 {
+                let inner_buf = this.data.fill_buf().unwrap_or_default();
+                let amt = std::cmp::min(inner_buf.len(), buf.len());
+                buf.put_slice(&inner_buf[.. amt]);
+                this.data.consume(amt);
+                return Ok(Poll::Ready(amt));
+            }
+
+            if let Some(view) = this.view.take() {
+                // Copy straight from JS memory into the caller's buffer, slicing off what remains.
+                let len = view.length();
+                if buf.len() >= len as usize {
+                    view.copy_to(&mut buf[.. len as usize]);
+                    return Ok(Poll::Ready(len as usize));
+                }
+                let amt = buf.len() as u32;
+                view.subarray(0, amt).copy_to(buf);
+                this.view = Some(view.subarray(amt, len));
+                return Ok(Poll::Ready(amt as usize));
+            }
+
+            match this.poll_chunk(cx)? {
+                Poll::Ready(true) => {},
+                Poll::Ready(false) => return Ok(Poll::Ready(0)),
+                Poll::Pending => return Ok(Poll::Pending),
             }
-        } else {
-            let amt = std::cmp::min(inner_buf.len(), buf.len());
-            buf.put_slice(&inner_buf[.. amt]);
-            Pin::new(&mut this.data).consume(amt);
-            Ok(Poll::Ready(Ok(amt)))
         }
     }
 }
@@ -95,32 +287,26 @@ impl TryFrom<AsyncIterator> for JsAsyncRead {
     }
 }
 
-#[derive(Clone, Debug)]
-struct AsyncReadableError(JsValue);
-
-unsafe impl Send for AsyncReadableError {
-}
-unsafe impl Sync for AsyncReadableError {
-}
-
-impl std::fmt::Display for AsyncReadableError {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(fmt, "{:?}", self.0)
+impl io::AsyncRead for JsAsyncRead {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let classifier = self.classifier.clone();
+        match JsAsyncRead::poll_read(self, cx, buf) {
+            Ok(success) => success.map(Ok),
+            Err(error) => Poll::Ready(Err(error.into_io_error(&classifier))),
+        }
     }
 }
 
-impl std::error::Error for AsyncReadableError {
-}
+impl io::AsyncBufRead for JsAsyncRead {
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().data.consume(amt)
+    }
 
-impl io::AsyncRead for JsAsyncRead {
-    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
-        match JsAsyncRead::poll_read(self, cx, buf) {
-            Ok(success) => success,
-            Err(error) => {
-                let kind = io::ErrorKind::Other;
-                let error = AsyncReadableError(error);
-                Poll::Ready(Err(io::Error::new(kind, error)))
-            },
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Poll<std::io::Result<&[u8]>> {
+        let classifier = self.classifier.clone();
+        match JsAsyncRead::poll_fill_buf(self, cx) {
+            Ok(success) => success.map(Ok),
+            Err(error) => Poll::Ready(Err(error.into_io_error(&classifier))),
         }
     }
 }
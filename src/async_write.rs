@@ -0,0 +1,163 @@
+use crate::{error::ErrorClassifier, sys, JsStreamError};
+use futures_core::Future;
+use futures_util::io;
+use js_sys::{Function, Promise, Uint8Array};
+use std::{
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+enum Sink {
+    Writer(sys::WritableStreamDefaultWriter),
+    Callback(Function),
+}
+
+impl Sink {
+    fn write(&self, chunk: &Uint8Array) -> Result<Promise, JsValue> {
+        match self {
+            Sink::Writer(writer) => Ok(writer.write(chunk)),
+            Sink::Callback(callback) => {
+                let result = callback.call1(&JsValue::NULL, chunk)?;
+                Ok(Promise::resolve(&result))
+            },
+        }
+    }
+
+    fn close(&self) -> Option<Promise> {
+        match self {
+            Sink::Writer(writer) => Some(writer.close()),
+            Sink::Callback(_) => None,
+        }
+    }
+}
+
+impl Drop for Sink {
+    fn drop(&mut self) {
+        if let Sink::Writer(writer) = self {
+            writer.release_lock();
+        }
+    }
+}
+
+pub struct JsAsyncWrite {
+    sink: Sink,
+    /// The promise of the most recent write or close.
+    pending: Option<JsFuture>,
+    closed: bool,
+    classifier: ErrorClassifier,
+}
+
+impl JsAsyncWrite {
+    fn with_sink(sink: Sink) -> Self {
+        let pending = None;
+        let closed = false;
+        let classifier = Rc::new(JsStreamError::io_error_kind);
+        Self {
+            sink,
+            pending,
+            closed,
+            classifier,
+        }
+    }
+
+    /// Each write is passed to the writer as a [`js_sys::Uint8Array`]. The lock on the writer is
+    /// released on drop.
+    pub fn new(writer: sys::WritableStreamDefaultWriter) -> Self {
+        Self::with_sink(Sink::Writer(writer))
+    }
+
+    /// Acquires a writer for the [`WritableStream`](sys::WritableStream), failing if the stream is
+    /// already locked.
+    pub fn from_writable_stream(stream: &sys::WritableStream) -> Result<Self, JsValue> {
+        Ok(Self::new(stream.get_writer()?))
+    }
+
+    /// The callback is expected to have the signature `(chunk: Uint8Array) => Promise<void>`. A
+    /// callback which returns a non-promise value is treated as having completed synchronously.
+    pub fn from_callback(callback: Function) -> Self {
+        Self::with_sink(Sink::Callback(callback))
+    }
+
+    /// Set how errors are classified when surfaced through [`std::io::Error`]. Defaults to
+    /// [`JsStreamError::io_error_kind`].
+    pub fn with_error_classifier<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&JsStreamError) -> io::ErrorKind + 'static,
+    {
+        self.classifier = Rc::new(classifier);
+        self
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context) -> Result<Poll<()>, JsStreamError> {
+        if let Some(pending) = self.pending.as_mut() {
+            match Pin::new(pending).poll(cx) {
+                Poll::Ready(result) => {
+                    self.pending = None;
+                    result?;
+                },
+                Poll::Pending => return Ok(Poll::Pending),
+            }
+        }
+        Ok(Poll::Ready(()))
+    }
+
+    fn poll_write(&mut self, cx: &mut Context, buf: &[u8]) -> Result<Poll<usize>, JsStreamError> {
+        if self.closed {
+            return Err(JsStreamError::protocol("JsAsyncWrite has been closed"));
+        }
+        if self.poll_pending(cx)?.is_pending() {
+            return Ok(Poll::Pending);
+        }
+        if buf.is_empty() {
+            return Ok(Poll::Ready(0));
+        }
+        // NOTE: the chunk must be copied out of wasm memory since the sink may hold onto it
+        let chunk = Uint8Array::from(buf);
+        let promise = self.sink.write(&chunk)?;
+        self.pending = Some(JsFuture::from(promise));
+        Ok(Poll::Ready(buf.len()))
+    }
+
+    fn poll_close(&mut self, cx: &mut Context) -> Result<Poll<()>, JsStreamError> {
+        if self.poll_pending(cx)?.is_pending() {
+            return Ok(Poll::Pending);
+        }
+        if !self.closed {
+            self.closed = true;
+            if let Some(promise) = self.sink.close() {
+                self.pending = Some(JsFuture::from(promise));
+                return self.poll_pending(cx);
+            }
+        }
+        Ok(Poll::Ready(()))
+    }
+}
+
+impl io::AsyncWrite for JsAsyncWrite {
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        match this.poll_close(cx) {
+            Ok(success) => success.map(Ok),
+            Err(error) => Poll::Ready(Err(error.into_io_error(&this.classifier))),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        match this.poll_pending(cx) {
+            Ok(success) => success.map(Ok),
+            Err(error) => Poll::Ready(Err(error.into_io_error(&this.classifier))),
+        }
+    }
+
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        match this.poll_write(cx, buf) {
+            Ok(success) => success.map(Ok),
+            Err(error) => Poll::Ready(Err(error.into_io_error(&this.classifier))),
+        }
+    }
+}
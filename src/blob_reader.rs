@@ -0,0 +1,207 @@
+use crate::{error::ErrorClassifier, sys, JsStreamError};
+use bytes::BufMut;
+use futures_core::Future;
+use futures_util::io;
+use js_sys::Uint8Array;
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+use wasm_bindgen_futures::JsFuture;
+
+/// A block of the blob which has been fetched into wasm memory.
+struct Block {
+    index: u64,
+    data: Vec<u8>,
+}
+
+/// A random-access reader over a `Blob` (or `File`), which fetches blocks on demand with
+/// `blob.slice(start, end).arrayBuffer()` and keeps the most recently used ones cached.
+pub struct JsBlobReader {
+    blob: sys::Blob,
+    size: u64,
+    position: u64,
+    block_size: u64,
+    /// Cached blocks, from least to most recently used.
+    cache: VecDeque<Block>,
+    cache_size: usize,
+    /// The block being fetched.
+    pending: Option<(u64, JsFuture)>,
+    classifier: ErrorClassifier,
+}
+
+impl JsBlobReader {
+    /// Defaults to blocks of 64 KiB and a cache of 4 blocks.
+    pub fn new(blob: &sys::Blob) -> Self {
+        let size = blob.size() as u64;
+        let blob = blob.clone();
+        let position = 0;
+        let block_size = 64 * 1024;
+        let cache = VecDeque::new();
+        let cache_size = 4;
+        let pending = None;
+        let classifier = Rc::new(JsStreamError::io_error_kind);
+        Self {
+            blob,
+            size,
+            position,
+            block_size,
+            cache,
+            cache_size,
+            pending,
+            classifier,
+        }
+    }
+
+    /// Set how many bytes are fetched at a time.
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size.max(1) as u64;
+        self.cache.clear();
+        self.pending = None;
+        self
+    }
+
+    /// Set how many blocks are kept cached.
+    pub fn with_cache_size(mut self, cache_size: usize) -> Self {
+        self.cache_size = cache_size.max(1);
+        while self.cache.len() > self.cache_size {
+            self.cache.pop_front();
+        }
+        self
+    }
+
+    /// Set how errors are classified when surfaced through [`std::io::Error`]. Defaults to
+    /// [`JsStreamError::io_error_kind`].
+    pub fn with_error_classifier<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&JsStreamError) -> io::ErrorKind + 'static,
+    {
+        self.classifier = Rc::new(classifier);
+        self
+    }
+
+    /// The size of the blob in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Poll for the block holding the current position, returning its place in the cache, or
+    /// `None` at the end of the blob.
+    fn poll_block(&mut self, cx: &mut Context) -> Result<Poll<Option<usize>>, JsStreamError> {
+        if self.position >= self.size {
+            return Ok(Poll::Ready(None));
+        }
+        let index = self.position / self.block_size;
+
+        if let Some(slot) = self.cache.iter().position(|block| block.index == index) {
+            // Move the block to the back, as the most recently used.
+            let block = self.cache.remove(slot).unwrap();
+            self.cache.push_back(block);
+            return Ok(Poll::Ready(Some(self.cache.len() - 1)));
+        }
+
+        // NOTE: a fetch for another block, left over from before a seek, is abandoned
+        if !matches!(self.pending, Some((pending, _)) if pending == index) {
+            let start = index * self.block_size;
+            let end = std::cmp::min(start + self.block_size, self.size);
+            let promise = self.blob.slice(start as f64, end as f64).array_buffer();
+            self.pending = Some((index, JsFuture::from(promise)));
+        }
+
+        let (_, future) = self.pending.as_mut().unwrap();
+        match Pin::new(future).poll(cx) {
+            Poll::Ready(result) => {
+                self.pending = None;
+                let data = Uint8Array::new(&result?).to_vec();
+                if self.cache.len() >= self.cache_size {
+                    self.cache.pop_front();
+                }
+                self.cache.push_back(Block { index, data });
+                Ok(Poll::Ready(Some(self.cache.len() - 1)))
+            },
+            Poll::Pending => Ok(Poll::Pending),
+        }
+    }
+
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Result<Poll<&[u8]>, JsStreamError> {
+        let this = self.get_mut();
+        let slot = match this.poll_block(cx)? {
+            Poll::Ready(Some(slot)) => slot,
+            Poll::Ready(None) => return Ok(Poll::Ready(&[])),
+            Poll::Pending => return Ok(Poll::Pending),
+        };
+        let block = &this.cache[slot];
+        let offset = (this.position - block.index * this.block_size) as usize;
+        Ok(Poll::Ready(&block.data[offset ..]))
+    }
+
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, mut buf: &mut [u8]) -> Result<Poll<usize>, JsStreamError> {
+        let this = self.get_mut();
+        let amt = match Pin::new(&mut *this).poll_fill_buf(cx)? {
+            Poll::Ready(data) => {
+                let amt = std::cmp::min(data.len(), buf.len());
+                buf.put_slice(&data[.. amt]);
+                amt
+            },
+            Poll::Pending => return Ok(Poll::Pending),
+        };
+        this.position += amt as u64;
+        Ok(Poll::Ready(amt))
+    }
+}
+
+impl io::AsyncRead for JsBlobReader {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let classifier = self.classifier.clone();
+        match JsBlobReader::poll_read(self, cx, buf) {
+            Ok(success) => success.map(Ok),
+            Err(error) => Poll::Ready(Err(error.into_io_error(&classifier))),
+        }
+    }
+}
+
+impl io::AsyncBufRead for JsBlobReader {
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().position += amt as u64;
+    }
+
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Poll<std::io::Result<&[u8]>> {
+        let classifier = self.classifier.clone();
+        match JsBlobReader::poll_fill_buf(self, cx) {
+            Ok(success) => success.map(Ok),
+            Err(error) => Poll::Ready(Err(error.into_io_error(&classifier))),
+        }
+    }
+}
+
+impl io::AsyncSeek for JsBlobReader {
+    /// Seeking past the end of the blob is allowed, after which reads return `Ok(0)`.
+    fn poll_seek(self: Pin<&mut Self>, _: &mut Context, pos: io::SeekFrom) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+        let position = match pos {
+            io::SeekFrom::Start(offset) => Some(offset),
+            io::SeekFrom::End(offset) => offset_position(this.size, offset),
+            io::SeekFrom::Current(offset) => offset_position(this.position, offset),
+        };
+        match position {
+            Some(position) => {
+                this.position = position;
+                Poll::Ready(Ok(position))
+            },
+            None => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ))),
+        }
+    }
+}
+
+fn offset_position(base: u64, offset: i64) -> Option<u64> {
+    if offset >= 0 {
+        base.checked_add(offset as u64)
+    } else {
+        base.checked_sub(offset.unsigned_abs())
+    }
+}
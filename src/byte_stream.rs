@@ -0,0 +1,133 @@
+use crate::{
+    convert::{self, Chunk},
+    sys,
+    JsStream,
+    JsStreamError,
+    StringEncoding,
+};
+use bytes::Bytes;
+use futures_core::{stream::FusedStream, Future, Stream};
+use js_sys::{AsyncIterator, Uint8Array};
+use std::{
+    convert::TryFrom,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+/// A [`Stream`] of [`Bytes`] which preserves chunk boundaries, yielding one item per chunk of
+/// the source. Accepts the same chunk types as [`JsAsyncRead`](crate::JsAsyncRead):
+/// [`js_sys::JsString`], [`js_sys::ArrayBuffer`], any [`js_sys::ArrayBuffer`] view, or `Blob`.
+pub struct JsByteStream {
+    inner: JsStream<JsValue>,
+    /// Pending bytes of a [`Blob`](sys::Blob) chunk.
+    blob: Option<JsFuture>,
+    encoding: StringEncoding,
+}
+
+impl JsByteStream {
+    /// The inner [`js_sys::AsyncIterator`] is expected to yield chunks of the types accepted by
+    /// [`JsAsyncRead::new`](crate::JsAsyncRead::new).
+    pub fn new(inner: AsyncIterator) -> Result<Self, JsValue> {
+        Ok(JsStream::new(inner)?.into())
+    }
+
+    /// See [`JsStream::from_async_iterable`].
+    pub fn from_async_iterable(iterable: &JsValue) -> Result<Self, JsValue> {
+        Ok(JsStream::from_async_iterable(iterable)?.into())
+    }
+
+    /// See [`JsStream::from_sync_iterator`].
+    pub fn from_sync_iterator(inner: js_sys::Iterator) -> Result<Self, JsValue> {
+        Ok(JsStream::from_sync_iterator(inner)?.into())
+    }
+
+    /// See [`JsStream::from_readable_stream`].
+    pub fn from_readable_stream(stream: &sys::ReadableStream) -> Result<Self, JsValue> {
+        Ok(JsStream::from_readable_stream(stream)?.into())
+    }
+
+    /// Set how [`js_sys::JsString`] chunks are converted into bytes. Defaults to
+    /// [`StringEncoding::Utf8`].
+    pub fn with_string_encoding(mut self, encoding: StringEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// See [`JsStream::with_prefetch`].
+    pub fn with_prefetch(mut self, depth: usize) -> Self {
+        self.inner = self.inner.with_prefetch(depth);
+        self
+    }
+
+    /// See [`JsStream::cancel`].
+    pub async fn cancel(self) -> Result<(), JsValue> {
+        self.inner.cancel().await
+    }
+
+    /// Convert a chunk into bytes, or start reading it if it is a [`Blob`](sys::Blob).
+    fn load_chunk(&mut self, value: JsValue) -> Result<Option<Bytes>, JsStreamError> {
+        match convert::classify_chunk(value)? {
+            Chunk::Bytes(view) => Ok(Some(view.to_vec().into())),
+            Chunk::String(string) => Ok(Some(self.encoding.encode(&string)?.into())),
+            Chunk::Blob(blob) => {
+                self.blob = Some(JsFuture::from(blob.array_buffer()));
+                Ok(None)
+            },
+        }
+    }
+}
+
+impl From<JsStream<JsValue>> for JsByteStream {
+    fn from(inner: JsStream<JsValue>) -> Self {
+        let blob = None;
+        let encoding = Default::default();
+        Self { inner, blob, encoding }
+    }
+}
+
+impl TryFrom<AsyncIterator> for JsByteStream {
+    type Error = JsValue;
+
+    fn try_from(inner: AsyncIterator) -> Result<Self, JsValue> {
+        Self::new(inner)
+    }
+}
+
+impl Stream for JsByteStream {
+    type Item = Result<Bytes, JsStreamError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(blob) = this.blob.as_mut() {
+                let result = match Pin::new(blob).poll(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                };
+                this.blob = None;
+                return match result {
+                    Ok(buffer) => Poll::Ready(Some(Ok(Uint8Array::new(&buffer).to_vec().into()))),
+                    Err(error) => Poll::Ready(Some(Err(error.into()))),
+                };
+            }
+            let value = match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(value))) => value,
+                Poll::Ready(Some(Err(error))) => return Poll::Ready(Some(Err(error))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            // NOTE: a blob chunk loops back around to poll its bytes
+            if let Some(bytes) = this.load_chunk(value).transpose() {
+                return Poll::Ready(Some(bytes));
+            }
+        }
+    }
+}
+
+impl FusedStream for JsByteStream {
+    fn is_terminated(&self) -> bool {
+        self.blob.is_none() && self.inner.is_terminated()
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{sys, JsStreamError};
+use js_sys::{ArrayBuffer, JsString, SharedArrayBuffer, Uint8Array};
+use wasm_bindgen::{prelude::*, JsCast};
+
+/// Conversion from a [`JsValue`] produced on the JS side into a Rust value.
+///
+/// This is how [`JsStream`](crate::JsStream) decodes the values yielded by its source. It is
+/// implemented for [`JsValue`], the [`js_sys`] types, Rust primitives, [`String`], [`Vec<u8>`],
+/// and [`Option<T>`]. Any other [`JsCast`] type, e.g., one from `web_sys`, can be decoded by
+/// wrapping it in [`Cast`], while types defined in the same crate as the impl can also implement
+/// it with [`impl_from_js_value!`](crate::impl_from_js_value).
+pub trait FromJsValue: Sized {
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError>;
+}
+
+/// Implement [`FromJsValue`](crate::FromJsValue) for [`JsCast`](wasm_bindgen::JsCast) types
+/// through a checked cast.
+///
+/// ```ignore
+/// #[wasm_bindgen]
+/// extern {
+///     pub type Headers;
+/// }
+///
+/// js_sys_futures::impl_from_js_value!(Headers);
+/// ```
+#[macro_export]
+macro_rules! impl_from_js_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl $crate::FromJsValue for $ty {
+                fn from_js_value(
+                    value: $crate::__wasm_bindgen::JsValue,
+                ) -> ::core::result::Result<Self, $crate::JsStreamError> {
+                    let result = $crate::__wasm_bindgen::JsCast::dyn_into::<$ty>(value);
+                    result.map_err($crate::JsStreamError::type_cast::<$ty>)
+                }
+            }
+        )*
+    };
+}
+
+/// Decodes any [`JsCast`] type through a checked cast, for types which cannot implement
+/// [`FromJsValue`] themselves because neither is defined in the caller's crate.
+///
+/// ```ignore
+/// let stream = JsStream::<Cast<web_sys::Response>>::new(iter)?;
+/// let responses = stream.map_ok(|Cast(response)| response);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cast<T>(pub T);
+
+impl<T: JsCast> FromJsValue for Cast<T> {
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+        value.dyn_into().map(Cast).map_err(JsStreamError::type_cast::<T>)
+    }
+}
+
+/// View the bytes of an [`ArrayBuffer`], a [`SharedArrayBuffer`], or any [`ArrayBuffer`] view
+/// (typed arrays, [`js_sys::DataView`], Node `Buffer`) as a [`Uint8Array`], without copying.
+pub(crate) fn as_uint8_array(value: &JsValue) -> Option<Uint8Array> {
+    if Uint8Array::instanceof(value) {
+        Some(value.clone().unchecked_into())
+    } else if ArrayBuffer::is_view(value) {
+        let view = value.unchecked_ref::<sys::ArrayBufferView>();
+        let buffer = view.buffer();
+        Some(Uint8Array::new_with_byte_offset_and_length(
+            &buffer,
+            view.byte_offset(),
+            view.byte_length(),
+        ))
+    } else if ArrayBuffer::instanceof(value) || SharedArrayBuffer::instanceof(value) {
+        Some(Uint8Array::new(value))
+    } else {
+        None
+    }
+}
+
+/// A chunk of bytes as produced for [`JsAsyncRead`](crate::JsAsyncRead) and
+/// [`JsByteStream`](crate::JsByteStream).
+pub(crate) enum Chunk {
+    /// The bytes of an [`ArrayBuffer`], a [`SharedArrayBuffer`], or any [`ArrayBuffer`] view.
+    Bytes(Uint8Array),
+    /// A string, which is still to be encoded.
+    String(JsString),
+    /// A `Blob`, whose bytes are still to be read.
+    Blob(sys::Blob),
+}
+
+/// Classify a chunk of bytes, failing with a protocol error if it is of an unsupported type.
+pub(crate) fn classify_chunk(value: JsValue) -> Result<Chunk, JsStreamError> {
+    if let Some(view) = as_uint8_array(&value) {
+        Ok(Chunk::Bytes(view))
+    } else if value.is_string() {
+        Ok(Chunk::String(value.unchecked_into()))
+    } else if sys::Blob::instanceof(&value) {
+        Ok(Chunk::Blob(value.unchecked_into()))
+    } else {
+        Err(JsStreamError::protocol(
+            "Inner AsyncIterator must produce a JsString, ArrayBuffer, ArrayBuffer view, or Blob",
+        ))
+    }
+}
+
+impl FromJsValue for JsValue {
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+        Ok(value)
+    }
+}
+
+impl_from_js_value!(
+    js_sys::Array,
+    js_sys::ArrayBuffer,
+    js_sys::AsyncIterator,
+    js_sys::Boolean,
+    js_sys::DataView,
+    js_sys::Date,
+    js_sys::Error,
+    js_sys::EvalError,
+    js_sys::Float32Array,
+    js_sys::Float64Array,
+    js_sys::Function,
+    js_sys::Int16Array,
+    js_sys::Int32Array,
+    js_sys::Int8Array,
+    js_sys::Iterator,
+    js_sys::JsString,
+    js_sys::Map,
+    js_sys::Number,
+    js_sys::Object,
+    js_sys::Promise,
+    js_sys::RangeError,
+    js_sys::ReferenceError,
+    js_sys::RegExp,
+    js_sys::Set,
+    js_sys::SharedArrayBuffer,
+    js_sys::Symbol,
+    js_sys::SyntaxError,
+    js_sys::TypeError,
+    js_sys::Uint16Array,
+    js_sys::Uint32Array,
+    js_sys::Uint8Array,
+    js_sys::Uint8ClampedArray,
+    js_sys::UriError,
+    js_sys::WeakMap,
+    js_sys::WeakSet,
+    js_sys::Intl::Collator,
+    js_sys::Intl::DateTimeFormat,
+    js_sys::Intl::NumberFormat,
+    js_sys::Intl::PluralRules,
+    js_sys::WebAssembly::CompileError,
+    js_sys::WebAssembly::Instance,
+    js_sys::WebAssembly::LinkError,
+    js_sys::WebAssembly::Memory,
+    js_sys::WebAssembly::Module,
+    js_sys::WebAssembly::RuntimeError,
+    js_sys::WebAssembly::Table,
+    sys::ArrayBufferView,
+    sys::Blob,
+    sys::EventEmitter,
+    sys::EventTarget,
+    sys::ReadableStream,
+    sys::ReadableStreamByobReader,
+    sys::ReadableStreamDefaultController,
+    sys::ReadableStreamDefaultReader,
+    sys::WritableStream,
+    sys::WritableStreamDefaultWriter,
+);
+
+impl FromJsValue for bool {
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+        value.as_bool().ok_or_else(|| JsStreamError::type_cast::<Self>(value))
+    }
+}
+
+impl FromJsValue for f64 {
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+        value.as_f64().ok_or_else(|| JsStreamError::type_cast::<Self>(value))
+    }
+}
+
+impl FromJsValue for f32 {
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+        f64::from_js_value(value).map(|number| number as f32)
+    }
+}
+
+macro_rules! impl_from_js_value_for_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl FromJsValue for $ty {
+                /// Only numbers which are integral and within range are accepted.
+                fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+                    match value.as_f64() {
+                        Some(number)
+                            if number.fract() == 0.0 && number >= <$ty>::MIN as f64 && number <= <$ty>::MAX as f64 =>
+                        {
+                            Ok(number as $ty)
+                        },
+                        _ => Err(JsStreamError::type_cast::<Self>(value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_js_value_for_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl FromJsValue for String {
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+        value.as_string().ok_or_else(|| JsStreamError::type_cast::<Self>(value))
+    }
+}
+
+impl FromJsValue for Vec<u8> {
+    /// Accepts an [`ArrayBuffer`], a [`SharedArrayBuffer`], or any [`ArrayBuffer`] view.
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+        match as_uint8_array(&value) {
+            Some(view) => Ok(view.to_vec()),
+            None => Err(JsStreamError::type_cast::<Self>(value)),
+        }
+    }
+}
+
+impl<T: FromJsValue> FromJsValue for Option<T> {
+    /// `null` and `undefined` become [`None`].
+    fn from_js_value(value: JsValue) -> Result<Self, JsStreamError> {
+        if value.is_null() || value.is_undefined() {
+            Ok(None)
+        } else {
+            T::from_js_value(value).map(Some)
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{sys, JsStream, JsStreamError};
+use futures_core::{stream::FusedStream, Stream};
+use js_sys::AsyncIterator;
+use serde::de::DeserializeOwned;
+use std::{
+    convert::TryFrom,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use wasm_bindgen::prelude::*;
+
+/// A [`JsStream`] which deserializes each yielded value into `T` with
+/// [`serde_wasm_bindgen`]. Values which fail to deserialize produce a
+/// [`JsStreamError::Deserialize`] carrying their index, and the stream carries on.
+pub struct JsDeserializeStream<T: DeserializeOwned> {
+    inner: JsStream<JsValue>,
+    index: usize,
+    phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> JsDeserializeStream<T> {
+    /// The inner [`js_sys::AsyncIterator`] is expected to yield values which deserialize as `T`.
+    pub fn new(inner: AsyncIterator) -> Result<Self, JsValue> {
+        Ok(JsStream::new(inner)?.into())
+    }
+
+    /// See [`JsStream::from_async_iterable`].
+    pub fn from_async_iterable(iterable: &JsValue) -> Result<Self, JsValue> {
+        Ok(JsStream::from_async_iterable(iterable)?.into())
+    }
+
+    /// The [`ReadableStream`](sys::ReadableStream) is read through a default reader, which is
+    /// acquired here and released on drop. It is expected to yield values which deserialize as `T`.
+    pub fn from_readable_stream(stream: &sys::ReadableStream) -> Result<Self, JsValue> {
+        Ok(JsStream::from_readable_stream(stream)?.into())
+    }
+
+    /// See [`JsStream::with_prefetch`].
+    pub fn with_prefetch(mut self, depth: usize) -> Self {
+        self.inner = self.inner.with_prefetch(depth);
+        self
+    }
+
+    /// See [`JsStream::cancel`].
+    pub async fn cancel(self) -> Result<(), JsValue> {
+        self.inner.cancel().await
+    }
+}
+
+impl<T: DeserializeOwned> From<JsStream<JsValue>> for JsDeserializeStream<T> {
+    fn from(inner: JsStream<JsValue>) -> Self {
+        let index = 0;
+        let phantom = std::marker::PhantomData;
+        Self { inner, index, phantom }
+    }
+}
+
+impl<T: DeserializeOwned> TryFrom<AsyncIterator> for JsDeserializeStream<T> {
+    type Error = JsValue;
+
+    fn try_from(inner: AsyncIterator) -> Result<Self, JsValue> {
+        Self::new(inner)
+    }
+}
+
+impl<T: DeserializeOwned> Stream for JsDeserializeStream<T> {
+    type Item = Result<T, JsStreamError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.inner).poll_next(cx) {
+            Poll::Ready(Some(result)) => {
+                let index = this.index;
+                this.index += 1;
+                let item = result.and_then(|value| {
+                    serde_wasm_bindgen::from_value(value).map_err(|error| JsStreamError::Deserialize {
+                        index,
+                        error: error.into(),
+                    })
+                });
+                Poll::Ready(Some(item))
+            },
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> FusedStream for JsDeserializeStream<T> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
@@ -0,0 +1,98 @@
+use crate::JsStreamError;
+use js_sys::{JsString, Uint16Array};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(inline_js = r#"
+export function codeUnits(string) {
+    const units = new Uint16Array(string.length);
+    for (let i = 0; i < string.length; i++) {
+        units[i] = string.charCodeAt(i);
+    }
+    return units;
+}
+"#)]
+extern {
+    /// Copy out the UTF-16 code units of a string in a single call, rather than one
+    /// `charCodeAt` call per code unit through [`JsString::iter`].
+    #[wasm_bindgen(js_name = codeUnits)]
+    fn code_units(string: &JsString) -> Uint16Array;
+}
+
+/// How [`js_sys::JsString`] chunks are converted into bytes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StringEncoding {
+    /// UTF-8, failing on strings which contain unpaired surrogates.
+    #[default]
+    Utf8,
+    /// UTF-8, replacing unpaired surrogates with `U+FFFD REPLACEMENT CHARACTER`.
+    Utf8Lossy,
+    /// [WTF-8](https://simonsapin.github.io/wtf-8/), encoding unpaired surrogates as 3-byte sequences.
+    Wtf8,
+    /// Latin-1 "binary strings", one byte per code unit, failing on code units above `0xFF`.
+    Latin1,
+    /// Raw UTF-16 code units in little-endian byte order.
+    Utf16Le,
+    /// Raw UTF-16 code units in big-endian byte order.
+    Utf16Be,
+}
+
+impl StringEncoding {
+    pub(crate) fn encode(self, string: &JsString) -> Result<Vec<u8>, JsStreamError> {
+        match self {
+            StringEncoding::Utf8 => {
+                let units = code_units(string).to_vec();
+                String::from_utf16(&units).map(String::into_bytes).map_err(|_| {
+                    JsStreamError::protocol("JsString contains unpaired surrogates and is not valid UTF-8")
+                })
+            },
+            StringEncoding::Utf8Lossy => {
+                // NOTE: strings are passed into wasm through `TextEncoder`, which already replaces
+                // unpaired surrogates
+                string
+                    .as_string()
+                    .map(String::into_bytes)
+                    .ok_or_else(|| JsStreamError::protocol("Error converting JsString to String"))
+            },
+            StringEncoding::Wtf8 => {
+                let units = code_units(string).to_vec();
+                let mut bytes = Vec::with_capacity(units.len());
+                for result in std::char::decode_utf16(units) {
+                    match result {
+                        Ok(char) => {
+                            let mut buf = [0u8; 4];
+                            bytes.extend_from_slice(char.encode_utf8(&mut buf).as_bytes());
+                        },
+                        Err(error) => {
+                            let unit = error.unpaired_surrogate();
+                            bytes.push(0xe0 | (unit >> 12) as u8);
+                            bytes.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                            bytes.push(0x80 | (unit & 0x3f) as u8);
+                        },
+                    }
+                }
+                Ok(bytes)
+            },
+            StringEncoding::Latin1 => {
+                let units = code_units(string).to_vec();
+                let mut bytes = Vec::with_capacity(units.len());
+                for unit in units {
+                    if unit > 0xff {
+                        return Err(JsStreamError::protocol(
+                            "JsString contains code units above 0xFF and is not a binary string",
+                        ));
+                    }
+                    bytes.push(unit as u8);
+                }
+                Ok(bytes)
+            },
+            StringEncoding::Utf16Le => {
+                let units = code_units(string).to_vec();
+                Ok(units.into_iter().flat_map(u16::to_le_bytes).collect())
+            },
+            StringEncoding::Utf16Be => {
+                let units = code_units(string).to_vec();
+                Ok(units.into_iter().flat_map(u16::to_be_bytes).collect())
+            },
+        }
+    }
+}
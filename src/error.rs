@@ -0,0 +1,243 @@
+use futures_util::io;
+use js_sys::Reflect;
+use std::{fmt, rc::Rc};
+use wasm_bindgen::{prelude::*, JsCast};
+
+/// Classifies errors surfaced through [`std::io::Error`].
+pub(crate) type ErrorClassifier = Rc<dyn Fn(&JsStreamError) -> io::ErrorKind>;
+
+/// An error produced by [`JsStream`](crate::JsStream), [`JsAsyncRead`](crate::JsAsyncRead),
+/// [`JsAsyncWrite`](crate::JsAsyncWrite), or [`JsPromise`](crate::JsPromise).
+///
+/// Errors surfaced through [`std::io::Error`] are classified with
+/// [`JsStreamError::io_error_kind`] (unless a custom classifier is installed) and can be recovered
+/// with `error.get_ref().and_then(|error| error.downcast_ref::<JsStreamError>())`. With wasm
+/// threads (`target_feature = "atomics"`), the error is not `Send`, so only its message is kept.
+#[derive(Clone, Debug)]
+pub enum JsStreamError {
+    /// The JS side produced a value which breaks the expected protocol, e.g., a chunk of an
+    /// unsupported type or a string which cannot be encoded. Holds the `TypeError` describing the
+    /// violation.
+    Protocol(js_sys::Error),
+    /// A promise was rejected or a JS call threw. Holds the rejection value.
+    Rejected(JsValue),
+    /// A yielded value could not be converted to the expected type.
+    TypeCast {
+        /// The value which failed to convert.
+        value: JsValue,
+        /// The name of the expected type.
+        expected: &'static str,
+    },
+    /// A yielded value could not be deserialized by
+    /// [`JsDeserializeStream`](crate::JsDeserializeStream) (requires the `serde` feature).
+    Deserialize {
+        /// The position of the value in the stream, counting from `0`.
+        index: usize,
+        /// The error raised while deserializing.
+        error: JsValue,
+    },
+}
+
+// NOTE: JS values are confined to the thread which owns the JS heap, which is only guaranteed while
+// wasm threads are unavailable
+#[cfg(not(target_feature = "atomics"))]
+unsafe impl Send for JsStreamError {
+}
+#[cfg(not(target_feature = "atomics"))]
+unsafe impl Sync for JsStreamError {
+}
+
+impl JsStreamError {
+    pub(crate) fn protocol(message: &str) -> Self {
+        JsStreamError::Protocol(js_sys::TypeError::new(message).into())
+    }
+
+    /// Build a [`TypeCast`](JsStreamError::TypeCast) error for a value which is not a `T`.
+    pub fn type_cast<T>(value: JsValue) -> Self {
+        let expected = std::any::type_name::<T>();
+        JsStreamError::TypeCast { value, expected }
+    }
+
+    /// The original JS value: the `TypeError` for [`Protocol`](JsStreamError::Protocol), the
+    /// rejection value for [`Rejected`](JsStreamError::Rejected), the unconverted value for
+    /// [`TypeCast`](JsStreamError::TypeCast), or the deserializer's error for
+    /// [`Deserialize`](JsStreamError::Deserialize).
+    pub fn value(&self) -> &JsValue {
+        match self {
+            JsStreamError::Protocol(error) => error,
+            JsStreamError::Rejected(value) => value,
+            JsStreamError::TypeCast { value, .. } => value,
+            JsStreamError::Deserialize { error, .. } => error,
+        }
+    }
+
+    /// Consume the error, returning the original JS value (see [`JsStreamError::value`]).
+    pub fn into_value(self) -> JsValue {
+        match self {
+            JsStreamError::Protocol(error) => error.into(),
+            JsStreamError::Rejected(value) => value,
+            JsStreamError::TypeCast { value, .. } => value,
+            JsStreamError::Deserialize { error, .. } => error,
+        }
+    }
+
+    /// Classify the error for [`std::io::Error`]. Protocol and type-cast failures are
+    /// [`InvalidData`](io::ErrorKind::InvalidData), while rejections are classified by the Node
+    /// `code` or the DOM exception `name` of the JS error, falling back to
+    /// [`Other`](io::ErrorKind::Other).
+    pub fn io_error_kind(&self) -> io::ErrorKind {
+        match self {
+            JsStreamError::Protocol(_) | JsStreamError::TypeCast { .. } | JsStreamError::Deserialize { .. } => {
+                io::ErrorKind::InvalidData
+            },
+            JsStreamError::Rejected(_) => {
+                let kind = self.code().and_then(|code| match code.as_str() {
+                    "ENOENT" => Some(io::ErrorKind::NotFound),
+                    "EACCES" | "EPERM" => Some(io::ErrorKind::PermissionDenied),
+                    "ECONNREFUSED" => Some(io::ErrorKind::ConnectionRefused),
+                    "ECONNRESET" => Some(io::ErrorKind::ConnectionReset),
+                    "ECONNABORTED" => Some(io::ErrorKind::ConnectionAborted),
+                    "ENOTCONN" => Some(io::ErrorKind::NotConnected),
+                    "EADDRINUSE" => Some(io::ErrorKind::AddrInUse),
+                    "EADDRNOTAVAIL" => Some(io::ErrorKind::AddrNotAvailable),
+                    "EPIPE" | "ERR_STREAM_DESTROYED" => Some(io::ErrorKind::BrokenPipe),
+                    "EEXIST" => Some(io::ErrorKind::AlreadyExists),
+                    "EAGAIN" | "EWOULDBLOCK" => Some(io::ErrorKind::WouldBlock),
+                    "EINVAL" => Some(io::ErrorKind::InvalidInput),
+                    "ETIMEDOUT" => Some(io::ErrorKind::TimedOut),
+                    "EINTR" | "ABORT_ERR" => Some(io::ErrorKind::Interrupted),
+                    "ERR_STREAM_PREMATURE_CLOSE" => Some(io::ErrorKind::UnexpectedEof),
+                    _ => None,
+                });
+                let kind = kind.or_else(|| {
+                    self.name().and_then(|name| match name.as_str() {
+                        "AbortError" => Some(io::ErrorKind::Interrupted),
+                        "TimeoutError" => Some(io::ErrorKind::TimedOut),
+                        "NotFoundError" => Some(io::ErrorKind::NotFound),
+                        "NotAllowedError" | "SecurityError" => Some(io::ErrorKind::PermissionDenied),
+                        "NotSupportedError" => Some(io::ErrorKind::Unsupported),
+                        "NetworkError" => Some(io::ErrorKind::ConnectionAborted),
+                        "EncodingError" | "DataError" => Some(io::ErrorKind::InvalidData),
+                        _ => None,
+                    })
+                });
+                kind.unwrap_or(io::ErrorKind::Other)
+            },
+        }
+    }
+
+    pub(crate) fn into_io_error(self, classifier: &ErrorClassifier) -> io::Error {
+        let kind = classifier(&self);
+        self.into_io_error_with_kind(kind)
+    }
+
+    fn into_io_error_with_kind(self, kind: io::ErrorKind) -> io::Error {
+        #[cfg(not(target_feature = "atomics"))]
+        return io::Error::new(kind, self);
+        // NOTE: with wasm threads the error is not `Send`, so only its message is kept
+        #[cfg(target_feature = "atomics")]
+        return io::Error::new(kind, self.to_string());
+    }
+
+    /// Convert an [`io::Error`] into a JS value, recovering the original value if it wraps a
+    /// [`JsStreamError`] and building a JS `Error` from its message otherwise.
+    pub(crate) fn from_io_error(error: io::Error) -> JsValue {
+        match error.get_ref().and_then(|inner| inner.downcast_ref::<JsStreamError>()) {
+            Some(inner) => inner.value().clone(),
+            None => js_sys::Error::new(&error.to_string()).into(),
+        }
+    }
+
+    /// Read a property of the JS error, if there is one and it is defined.
+    fn property(&self, key: &str) -> Option<JsValue> {
+        match self {
+            JsStreamError::TypeCast { .. } => None,
+            _ if self.value().is_object() => Reflect::get(self.value(), &key.into())
+                .ok()
+                .filter(|value| !value.is_undefined()),
+            _ => None,
+        }
+    }
+
+    /// The `name` of the JS error, e.g., `"TypeError"` or `"AbortError"`.
+    pub fn name(&self) -> Option<String> {
+        self.property("name").and_then(|name| name.as_string())
+    }
+
+    /// The `message` of the JS error. A rejection with a bare string is taken as the message.
+    pub fn message(&self) -> Option<String> {
+        match self {
+            JsStreamError::Rejected(value) if value.is_string() => value.as_string(),
+            _ => self.property("message").and_then(|message| message.as_string()),
+        }
+    }
+
+    /// The `code` of the JS error, as set on Node system errors, e.g., `"ENOENT"`.
+    pub fn code(&self) -> Option<String> {
+        self.property("code").and_then(|code| code.as_string())
+    }
+
+    /// The `stack` of the JS error, where the runtime provides one.
+    pub fn stack(&self) -> Option<String> {
+        self.property("stack").and_then(|stack| stack.as_string())
+    }
+
+    /// The `cause` of the JS error, where one was given.
+    pub fn cause(&self) -> Option<JsValue> {
+        self.property("cause")
+    }
+}
+
+impl fmt::Display for JsStreamError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if let JsStreamError::TypeCast { value, expected } = self {
+            return write!(fmt, "expected a value of type `{}`, got {:?}", expected, value);
+        }
+        if let JsStreamError::Deserialize { index, .. } = self {
+            let message = self.message().unwrap_or_else(|| format!("{:?}", self.value()));
+            return write!(fmt, "failed to deserialize item {}: {}", index, message);
+        }
+        match (self.name(), self.message()) {
+            (Some(name), Some(message)) => write!(fmt, "{}: {}", name, message),
+            (None, Some(message)) => write!(fmt, "{}", message),
+            _ => write!(fmt, "{:?}", self.value()),
+        }
+    }
+}
+
+impl std::error::Error for JsStreamError {
+}
+
+impl From<JsValue> for JsStreamError {
+    fn from(value: JsValue) -> Self {
+        JsStreamError::Rejected(value)
+    }
+}
+
+impl From<JsStreamError> for JsValue {
+    fn from(error: JsStreamError) -> Self {
+        error.into_value()
+    }
+}
+
+impl From<JsStreamError> for js_sys::Error {
+    /// The JS `Error` is passed through where there is one, while any other value is wrapped in a
+    /// new `Error` with the value as its `cause`.
+    fn from(error: JsStreamError) -> Self {
+        let is_error = error.value().is_instance_of::<js_sys::Error>();
+        if is_error && !matches!(error, JsStreamError::TypeCast { .. }) {
+            return error.into_value().unchecked_into();
+        }
+        let wrapper = js_sys::Error::new(&error.to_string());
+        // NOTE: setting a property on a fresh error cannot fail
+        Reflect::set(&wrapper, &"cause".into(), error.value()).ok();
+        wrapper
+    }
+}
+
+impl From<JsStreamError> for io::Error {
+    fn from(error: JsStreamError) -> Self {
+        let kind = error.io_error_kind();
+        error.into_io_error_with_kind(kind)
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{sys, FromJsValue, JsStreamError};
+use futures_core::Stream;
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+use wasm_bindgen::{prelude::*, JsCast};
+
+/// What a bounded [`JsEventStream`] does with an event which arrives while its queue is full.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// Discard the incoming event.
+    #[default]
+    DropNewest,
+    /// Discard the oldest queued event to make room for the incoming one.
+    DropOldest,
+    /// Yield the queued events, then an error, and end the stream.
+    Fail,
+}
+
+enum Source {
+    EventTarget(sys::EventTarget),
+    EventEmitter(sys::EventEmitter),
+}
+
+/// Events received by the listener which have not been yielded yet.
+struct Queue {
+    events: VecDeque<JsValue>,
+    /// The maximum number of queued events, if bounded.
+    capacity: Option<usize>,
+    overflow: OverflowPolicy,
+    /// Whether an event was lost under [`OverflowPolicy::Fail`].
+    overflowed: bool,
+    waker: Option<Waker>,
+}
+
+impl Queue {
+    fn push(&mut self, event: JsValue) {
+        if self.overflowed {
+            return;
+        }
+        if let Some(capacity) = self.capacity {
+            if self.events.len() >= capacity {
+                match self.overflow {
+                    OverflowPolicy::DropNewest => return,
+                    OverflowPolicy::DropOldest => {
+                        self.events.pop_front();
+                    },
+                    OverflowPolicy::Fail => {
+                        self.overflowed = true;
+                    },
+                }
+            }
+        }
+        if !self.overflowed {
+            self.events.push_back(event);
+        }
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// A [`Stream`] of the events dispatched by a JS event source, decoded as `T` through
+/// [`FromJsValue`].
+///
+/// The listener is added on construction and removed on drop, which also frees its [`Closure`].
+/// Events are queued without bound unless [`JsEventStream::with_capacity`] is used.
+pub struct JsEventStream<T: FromJsValue> {
+    source: Source,
+    event: String,
+    listener: Closure<dyn FnMut(JsValue)>,
+    queue: Rc<RefCell<Queue>>,
+    /// Whether the stream has ended after an overflow.
+    done: bool,
+    phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: FromJsValue> JsEventStream<T> {
+    fn with_source(source: Source, event: &str) -> Result<Self, JsValue> {
+        let queue = Rc::new(RefCell::new(Queue {
+            events: VecDeque::new(),
+            capacity: None,
+            overflow: OverflowPolicy::default(),
+            overflowed: false,
+            waker: None,
+        }));
+        let listener = {
+            let queue = queue.clone();
+            Closure::wrap(Box::new(move |event: JsValue| {
+                queue.borrow_mut().push(event);
+            }) as Box<dyn FnMut(JsValue)>)
+        };
+        let function = listener.as_ref().unchecked_ref();
+        match &source {
+            Source::EventTarget(target) => target.add_event_listener(event, function)?,
+            Source::EventEmitter(emitter) => {
+                emitter.on(event, function)?;
+            },
+        }
+        let event = event.into();
+        let done = false;
+        let phantom = std::marker::PhantomData;
+        Ok(Self {
+            source,
+            event,
+            listener,
+            queue,
+            done,
+            phantom,
+        })
+    }
+
+    /// Listen for `event` with `addEventListener`, as supported by DOM objects and by Node's
+    /// `EventTarget`.
+    pub fn from_event_target(target: &sys::EventTarget, event: &str) -> Result<Self, JsValue> {
+        let target = target.clone();
+        Self::with_source(Source::EventTarget(target), event)
+    }
+
+    /// Listen for `event` with the `on` method of a Node `EventEmitter`. Only the first argument
+    /// passed to the listener is yielded.
+    pub fn from_event_emitter(emitter: &sys::EventEmitter, event: &str) -> Result<Self, JsValue> {
+        let emitter = emitter.clone();
+        Self::with_source(Source::EventEmitter(emitter), event)
+    }
+
+    /// Queue at most `capacity` events, handling any more according to `overflow`.
+    pub fn with_capacity(self, capacity: usize, overflow: OverflowPolicy) -> Self {
+        {
+            let mut queue = self.queue.borrow_mut();
+            queue.capacity = Some(capacity.max(1));
+            queue.overflow = overflow;
+        }
+        self
+    }
+}
+
+impl<T: FromJsValue> Stream for JsEventStream<T> {
+    type Item = Result<T, JsStreamError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        let mut queue = this.queue.borrow_mut();
+        if let Some(event) = queue.events.pop_front() {
+            return Poll::Ready(Some(T::from_js_value(event)));
+        }
+        if queue.overflowed {
+            this.done = true;
+            let message = format!("JsEventStream queue overflowed on \"{}\" event", this.event);
+            return Poll::Ready(Some(Err(JsStreamError::protocol(&message))));
+        }
+        queue.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T: FromJsValue> Drop for JsEventStream<T> {
+    fn drop(&mut self) {
+        let function = self.listener.as_ref().unchecked_ref();
+        match &self.source {
+            Source::EventTarget(target) => {
+                target.remove_event_listener(&self.event, function).ok();
+            },
+            Source::EventEmitter(emitter) => {
+                emitter.remove_listener(&self.event, function).ok();
+            },
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{FromJsValue, JsStreamError};
+use js_sys::{Function, Reflect};
+use std::iter::FusedIterator;
+use wasm_bindgen::JsCast;
+
+/// A Rust [`Iterator`] over a sync [`js_sys::Iterator`], e.g., from `Array.prototype.values()`,
+/// `Map.prototype.entries()`, or a generator, decoding values as `T` through [`FromJsValue`] the
+/// same way [`JsStream`](crate::JsStream) does.
+///
+/// Promises yielded by the iterator are not awaited; use
+/// [`JsStream::from_sync_iterator`](crate::JsStream::from_sync_iterator) for that.
+pub struct JsIter<T: FromJsValue> {
+    inner: js_sys::Iterator,
+    /// Whether the iterator has been exhausted or has thrown, and so needs no cleanup on drop.
+    done: bool,
+    phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: FromJsValue> JsIter<T> {
+    /// The inner [`js_sys::Iterator`] is expected to yield values which decode as `T`. If the
+    /// iterator is dropped before it is exhausted, its `return()` method is called.
+    pub fn new(inner: js_sys::Iterator) -> Self {
+        let done = false;
+        let phantom = std::marker::PhantomData;
+        Self { inner, done, phantom }
+    }
+}
+
+impl<T: FromJsValue> From<js_sys::Iterator> for JsIter<T> {
+    fn from(inner: js_sys::Iterator) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T: FromJsValue> Iterator for JsIter<T> {
+    type Item = Result<T, JsStreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.inner.next() {
+            Ok(iterator_next) if iterator_next.done() => {
+                self.done = true;
+                None
+            },
+            Ok(iterator_next) => Some(T::from_js_value(iterator_next.value())),
+            Err(error) => {
+                self.done = true;
+                Some(Err(error.into()))
+            },
+        }
+    }
+}
+
+impl<T: FromJsValue> FusedIterator for JsIter<T> {
+}
+
+impl<T: FromJsValue> Drop for JsIter<T> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        // NOTE: lets generators run their `finally` blocks; errors thrown by `return()` are ignored
+        if let Ok(method) = Reflect::get(&self.inner, &"return".into()) {
+            if let Some(method) = method.dyn_ref::<Function>() {
+                method.call0(&self.inner).ok();
+            }
+        }
+    }
+}
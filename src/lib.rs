@@ -1,6 +1,39 @@
+mod async_iterator;
 mod async_read;
+mod async_write;
+mod blob_reader;
+mod byte_stream;
+mod convert;
+#[cfg(feature = "serde")]
+mod deserialize;
+mod encoding;
+mod error;
+mod event_stream;
+mod iter;
+mod promise;
+mod push_stream;
+mod readable_stream;
+mod source;
 mod stream;
+pub mod sys;
 
+pub use async_iterator::*;
 pub use async_read::*;
+pub use async_write::*;
+pub use blob_reader::*;
+pub use byte_stream::*;
+pub use convert::*;
+#[cfg(feature = "serde")]
+pub use deserialize::*;
+pub use encoding::*;
+pub use error::*;
+pub use event_stream::*;
+pub use iter::*;
+pub use promise::*;
+pub use push_stream::*;
+pub use readable_stream::*;
 pub use stream::*;
 pub use wasm_bindgen_futures::*;
+
+#[doc(hidden)]
+pub use wasm_bindgen as __wasm_bindgen;
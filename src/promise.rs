@@ -0,0 +1,122 @@
+use crate::{FromJsValue, JsStreamError};
+use futures_core::Future;
+use js_sys::{Array, Promise, Reflect};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+#[wasm_bindgen]
+extern {
+    #[wasm_bindgen(js_namespace = Promise, js_name = allSettled)]
+    fn all_settled(iterable: &JsValue) -> Promise;
+
+    #[wasm_bindgen(js_namespace = Promise, js_name = any)]
+    fn any(iterable: &JsValue) -> Promise;
+}
+
+/// A [`Promise`] as a future which decodes its resolved value as `T` through [`FromJsValue`].
+///
+/// Resolves to [`JsStreamError::Rejected`] if the promise is rejected, or to
+/// [`JsStreamError::TypeCast`] if it is fulfilled with a value which is not a `T`.
+pub struct JsPromise<T: FromJsValue = JsValue> {
+    inner: JsFuture,
+    phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: FromJsValue> JsPromise<T> {
+    /// The promise is expected to be fulfilled with a value which decodes as `T`.
+    pub fn new(promise: Promise) -> Self {
+        let inner = JsFuture::from(promise);
+        let phantom = std::marker::PhantomData;
+        Self { inner, phantom }
+    }
+}
+
+impl<T: FromJsValue> From<Promise> for JsPromise<T> {
+    fn from(promise: Promise) -> Self {
+        Self::new(promise)
+    }
+}
+
+impl<T: FromJsValue> Future for JsPromise<T> {
+    type Output = Result<T, JsStreamError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let inner = Pin::new(&mut self.get_mut().inner);
+        match inner.poll(cx) {
+            Poll::Ready(Ok(value)) => Poll::Ready(T::from_js_value(value)),
+            Poll::Ready(Err(error)) => Poll::Ready(Err(JsStreamError::Rejected(error))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+fn to_array<I>(values: I) -> Array
+where
+    I: IntoIterator,
+    I::Item: Into<JsValue>,
+{
+    values.into_iter().map(Into::into).collect()
+}
+
+fn decode_all<T: FromJsValue>(values: JsValue) -> Result<Vec<T>, JsStreamError> {
+    Array::from(&values).iter().map(T::from_js_value).collect()
+}
+
+/// Await every promise (or plain value) with `Promise.all`, decoding the results as `T`. Fails
+/// with the first rejection.
+pub async fn promise_all<T, I>(values: I) -> Result<Vec<T>, JsStreamError>
+where
+    T: FromJsValue,
+    I: IntoIterator,
+    I::Item: Into<JsValue>,
+{
+    let results = JsPromise::<JsValue>::new(Promise::all(&to_array(values))).await?;
+    decode_all(results)
+}
+
+/// Await every promise (or plain value) with `Promise.allSettled`, decoding each fulfilled value
+/// as `T` and reporting each rejection as [`JsStreamError::Rejected`].
+pub async fn promise_all_settled<T, I>(values: I) -> Result<Vec<Result<T, JsStreamError>>, JsStreamError>
+where
+    T: FromJsValue,
+    I: IntoIterator,
+    I::Item: Into<JsValue>,
+{
+    let results = JsPromise::<Array>::new(all_settled(&to_array(values))).await?;
+    let mut settled = Vec::with_capacity(results.length() as usize);
+    for result in results.iter() {
+        let status = Reflect::get(&result, &"status".into())?;
+        if status.as_string().as_deref() == Some("fulfilled") {
+            settled.push(T::from_js_value(Reflect::get(&result, &"value".into())?));
+        } else {
+            settled.push(Err(JsStreamError::Rejected(Reflect::get(&result, &"reason".into())?)));
+        }
+    }
+    Ok(settled)
+}
+
+/// Await the first promise (or plain value) to settle with `Promise.race`, decoding its value as
+/// `T`.
+pub async fn promise_race<T, I>(values: I) -> Result<T, JsStreamError>
+where
+    T: FromJsValue,
+    I: IntoIterator,
+    I::Item: Into<JsValue>,
+{
+    JsPromise::new(Promise::race(&to_array(values))).await
+}
+
+/// Await the first promise (or plain value) to fulfill with `Promise.any`, decoding its value as
+/// `T`. Fails with an `AggregateError` if every promise is rejected.
+pub async fn promise_any<T, I>(values: I) -> Result<T, JsStreamError>
+where
+    T: FromJsValue,
+    I: IntoIterator,
+    I::Item: Into<JsValue>,
+{
+    JsPromise::new(any(&to_array(values))).await
+}
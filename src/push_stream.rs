@@ -0,0 +1,170 @@
+use crate::{FromJsValue, JsStreamError};
+use futures_core::{stream::FusedStream, Stream};
+use js_sys::{Function, Promise, Reflect};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+use wasm_bindgen::{prelude::*, JsCast};
+
+/// State owned by a [`JsPushStream`], which its push function only holds weakly.
+struct Shared {
+    /// Pushed values and errors which have not been yielded yet.
+    items: VecDeque<Result<JsValue, JsValue>>,
+    capacity: usize,
+    /// Whether `end()` or `error()` has been called.
+    ended: bool,
+    waker: Option<Waker>,
+    /// Resolvers of the promises returned by `push` while the buffer was full.
+    waiters: Vec<Function>,
+}
+
+impl Shared {
+    fn push(&mut self, item: Result<JsValue, JsValue>) {
+        self.items.push_back(item);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn end(&mut self) {
+        self.ended = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Resolve the promises returned by `push` once there is room in the buffer again.
+    fn release(&mut self) {
+        if self.items.len() < self.capacity {
+            for resolve in self.waiters.drain(..) {
+                resolve.call0(&JsValue::UNDEFINED).ok();
+            }
+        }
+    }
+}
+
+/// Run `f` on the shared state, unless the stream has already been dropped.
+fn with_shared<R>(shared: &Weak<RefCell<Shared>>, f: impl FnOnce(&mut Shared) -> R) -> Option<R> {
+    shared.upgrade().map(|shared| f(&mut shared.borrow_mut()))
+}
+
+/// A [`Stream`] fed from JS through a push function, for bridging callback-style JS APIs. Pushed
+/// values are decoded as `T` through [`FromJsValue`].
+pub struct JsPushStream<T: FromJsValue> {
+    shared: Rc<RefCell<Shared>>,
+    phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: FromJsValue> JsPushStream<T> {
+    /// Create the stream along with its push function, to be handed to JS.
+    ///
+    /// Calling `push(value)` buffers a value. Once `capacity` values are buffered, `push` returns
+    /// a promise which resolves when the buffer has room again, and which callers may await for
+    /// backpressure. `push.end()` ends the stream after the buffered values, while
+    /// `push.error(e)` ends it with a [`JsStreamError::Rejected`] error. Values pushed after the
+    /// end are rejected, while those pushed after the stream is dropped are discarded.
+    ///
+    /// The push function only holds a weak reference to the buffer, which is freed along with
+    /// the stream. The closures behind `push`, `push.end`, and `push.error` themselves are only
+    /// freed by the JS garbage collector when wasm-bindgen's weak reference support
+    /// (`WASM_BINDGEN_WEAKREF`) is enabled, and are otherwise leaked.
+    pub fn new(capacity: usize) -> (Self, Function) {
+        let shared = Rc::new(RefCell::new(Shared {
+            items: VecDeque::new(),
+            capacity: capacity.max(1),
+            ended: false,
+            waker: None,
+            waiters: Vec::new(),
+        }));
+
+        let push = {
+            let shared = Rc::downgrade(&shared);
+            Closure::wrap(Box::new(move |value: JsValue| {
+                let pushed = with_shared(&shared, |shared| {
+                    if shared.ended {
+                        let error = js_sys::TypeError::new("JsPushStream has already ended");
+                        return Promise::reject(&error).into();
+                    }
+                    shared.push(Ok(value));
+                    if shared.items.len() < shared.capacity {
+                        return JsValue::UNDEFINED;
+                    }
+                    Promise::new(&mut |resolve, _| shared.waiters.push(resolve)).into()
+                });
+                pushed.unwrap_or(JsValue::UNDEFINED)
+            }) as Box<dyn FnMut(JsValue) -> JsValue>)
+            .into_js_value()
+            .unchecked_into::<Function>()
+        };
+
+        let end = {
+            let shared = Rc::downgrade(&shared);
+            Closure::wrap(Box::new(move || {
+                with_shared(&shared, Shared::end);
+            }) as Box<dyn FnMut()>)
+            .into_js_value()
+        };
+
+        let error = {
+            let shared = Rc::downgrade(&shared);
+            Closure::wrap(Box::new(move |error: JsValue| {
+                with_shared(&shared, |shared| {
+                    if !shared.ended {
+                        shared.push(Err(error));
+                        shared.end();
+                    }
+                });
+            }) as Box<dyn FnMut(JsValue)>)
+            .into_js_value()
+        };
+
+        // NOTE: setting properties on a function object cannot fail
+        Reflect::set(&push, &"end".into(), &end).ok();
+        Reflect::set(&push, &"error".into(), &error).ok();
+
+        let phantom = std::marker::PhantomData;
+        (Self { shared, phantom }, push)
+    }
+}
+
+impl<T: FromJsValue> Stream for JsPushStream<T> {
+    type Item = Result<T, JsStreamError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.borrow_mut();
+        match shared.items.pop_front() {
+            Some(item) => {
+                shared.release();
+                match item {
+                    Ok(value) => Poll::Ready(Some(T::from_js_value(value))),
+                    Err(error) => Poll::Ready(Some(Err(JsStreamError::Rejected(error)))),
+                }
+            },
+            None if shared.ended => Poll::Ready(None),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+impl<T: FromJsValue> FusedStream for JsPushStream<T> {
+    fn is_terminated(&self) -> bool {
+        let shared = self.shared.borrow();
+        shared.ended && shared.items.is_empty()
+    }
+}
+
+impl<T: FromJsValue> Drop for JsPushStream<T> {
+    fn drop(&mut self) {
+        // NOTE: pending pushes are resolved so that producers awaiting them are not stuck
+        for resolve in self.shared.borrow_mut().waiters.drain(..) {
+            resolve.call0(&JsValue::UNDEFINED).ok();
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{sys, JsStreamError};
+use futures_core::Stream;
+use futures_util::{
+    io::{AsyncRead, AsyncReadExt},
+    stream::StreamExt,
+};
+use js_sys::{Promise, Uint8Array};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+#[wasm_bindgen(inline_js = r#"
+export function createPullStream(pull, cancel, highWaterMark, byteLength) {
+    const strategy = { highWaterMark };
+    if (byteLength) {
+        strategy.size = (chunk) => chunk.byteLength;
+    }
+    return new ReadableStream({ pull, cancel }, strategy);
+}
+"#)]
+extern {
+    #[wasm_bindgen(js_name = createPullStream)]
+    fn create_pull_stream(
+        pull: &JsValue,
+        cancel: &JsValue,
+        high_water_mark: f64,
+        byte_length: bool,
+    ) -> sys::ReadableStream;
+}
+
+/// State shared between the `pull` and `cancel` callbacks of a stream.
+struct PullState<T> {
+    source: Option<T>,
+    cancelled: bool,
+}
+
+impl<T> PullState<T> {
+    fn new(source: T) -> Rc<RefCell<Self>> {
+        let source = Some(source);
+        let cancelled = false;
+        Rc::new(RefCell::new(Self { source, cancelled }))
+    }
+
+    fn cancel(&mut self) {
+        self.source = None;
+        self.cancelled = true;
+    }
+
+    /// Put the source back after a pull, unless the stream was cancelled meanwhile.
+    fn restore(&mut self, source: T) {
+        if !self.cancelled {
+            self.source = Some(source);
+        }
+    }
+}
+
+/// Expose a Rust [`AsyncRead`] to JS as a [`ReadableStream`](sys::ReadableStream) of
+/// [`Uint8Array`] chunks of at most `chunk_size` bytes.
+///
+/// The reader is only read from when the stream pulls, i.e., while fewer than `high_water_mark`
+/// bytes are queued. The reader is dropped at EOF, on error (which errors the stream), or when
+/// the stream is cancelled, after any read in progress completes.
+///
+/// The `pull` and `cancel` callbacks are handed to JS as closures, which wasm-bindgen only frees
+/// when its weak reference support (`WASM_BINDGEN_WEAKREF`) is enabled. Without it they are
+/// leaked, and so is the reader if JS drops the stream before reading it to the end or
+/// cancelling it.
+pub fn async_read_to_readable_stream<R>(reader: R, chunk_size: usize, high_water_mark: f64) -> sys::ReadableStream
+where
+    R: AsyncRead + 'static,
+{
+    let chunk_size = chunk_size.max(1);
+    let state = PullState::new(Box::pin(reader));
+
+    let pull = {
+        let state = state.clone();
+        Closure::wrap(Box::new(move |controller: sys::ReadableStreamDefaultController| {
+            let state = state.clone();
+            future_to_promise(async move {
+                // NOTE: the reader is taken out for the duration of the read so that a concurrent
+                // cancel can drop it without waiting
+                let source = state.borrow_mut().source.take();
+                let mut reader = match source {
+                    Some(reader) => reader,
+                    None => return Ok(JsValue::UNDEFINED),
+                };
+                let mut buf = vec![0; chunk_size];
+                match reader.read(&mut buf).await {
+                    Ok(0) => {
+                        controller.close()?;
+                    },
+                    Ok(amt) => {
+                        controller.enqueue(&Uint8Array::from(&buf[.. amt]))?;
+                        state.borrow_mut().restore(reader);
+                    },
+                    Err(error) => return Err(JsStreamError::from_io_error(error)),
+                }
+                Ok(JsValue::UNDEFINED)
+            })
+        })
+            as Box<dyn FnMut(sys::ReadableStreamDefaultController) -> Promise>)
+        .into_js_value()
+    };
+
+    let cancel = {
+        Closure::wrap(Box::new(move |_reason: JsValue| {
+            state.borrow_mut().cancel();
+            Promise::resolve(&JsValue::UNDEFINED)
+        }) as Box<dyn FnMut(JsValue) -> Promise>)
+        .into_js_value()
+    };
+
+    create_pull_stream(&pull, &cancel, high_water_mark, true)
+}
+
+/// Expose a Rust [`Stream`] to JS as an object-mode [`ReadableStream`](sys::ReadableStream), for
+/// use with APIs such as `Response`, `pipeTo`, or `TransformStream`.
+///
+/// An item is only pulled from the stream when the JS stream pulls, i.e., while fewer than
+/// `high_water_mark` items are queued or a read is waiting. An `Err` item errors the stream. The
+/// stream is dropped once it is exhausted or fails, or when the JS stream is cancelled, after any
+/// pull in progress completes.
+///
+/// As with [`async_read_to_readable_stream`], the callbacks are leaked when wasm-bindgen's weak
+/// reference support is not enabled, along with the stream if JS abandons the `ReadableStream`
+/// before it ends.
+pub fn stream_to_readable_stream<S, T, E>(stream: S, high_water_mark: f64) -> sys::ReadableStream
+where
+    S: Stream<Item = Result<T, E>> + 'static,
+    T: Into<JsValue>,
+    E: Into<JsValue>,
+{
+    let state = PullState::new(Box::pin(stream));
+
+    let pull = {
+        let state = state.clone();
+        Closure::wrap(Box::new(move |controller: sys::ReadableStreamDefaultController| {
+            let state = state.clone();
+            future_to_promise(async move {
+                let source = state.borrow_mut().source.take();
+                let mut stream = match source {
+                    Some(stream) => stream,
+                    None => return Ok(JsValue::UNDEFINED),
+                };
+                match stream.next().await {
+                    Some(Ok(item)) => {
+                        controller.enqueue(&item.into())?;
+                        state.borrow_mut().restore(stream);
+                    },
+                    Some(Err(error)) => controller.error(&error.into()),
+                    None => controller.close()?,
+                }
+                Ok(JsValue::UNDEFINED)
+            })
+        })
+            as Box<dyn FnMut(sys::ReadableStreamDefaultController) -> Promise>)
+        .into_js_value()
+    };
+
+    let cancel = {
+        Closure::wrap(Box::new(move |_reason: JsValue| {
+            state.borrow_mut().cancel();
+            Promise::resolve(&JsValue::UNDEFINED)
+        }) as Box<dyn FnMut(JsValue) -> Promise>)
+        .into_js_value()
+    };
+
+    create_pull_stream(&pull, &cancel, high_water_mark, false)
+}
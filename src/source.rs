@@ -0,0 +1,185 @@
+use crate::sys;
+use js_sys::{ArrayBuffer, AsyncIterator, Function, Object, Promise, Reflect, Symbol, Uint8Array};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+
+#[wasm_bindgen(inline_js = r#"
+export function syncNext(iterator) {
+    try {
+        const { value, done } = iterator.next();
+        return Promise.resolve(value).then((value) => ({ value, done }));
+    } catch (error) {
+        return Promise.reject(error);
+    }
+}
+"#)]
+extern {
+    /// Step a sync iterator the way `for await` does, awaiting values which are promises.
+    #[wasm_bindgen(js_name = syncNext)]
+    fn sync_next(iterator: &js_sys::Iterator) -> Promise;
+}
+
+enum Kind {
+    AsyncIterator(AsyncIterator),
+    Iterator(js_sys::Iterator),
+    Reader(sys::ReadableStreamDefaultReader),
+    ByobReader {
+        reader: sys::ReadableStreamByobReader,
+        /// The buffer handed to the next read, which is absent while a read is outstanding.
+        buffer: Option<ArrayBuffer>,
+        buffer_size: u32,
+    },
+}
+
+/// A JS object which produces `{ value, done }` results through promises.
+pub(crate) struct Source {
+    kind: Kind,
+    /// Whether the source has been exhausted or cancelled, and so needs no cleanup on drop.
+    done: bool,
+}
+
+impl Source {
+    fn with_kind(kind: Kind) -> Self {
+        let done = false;
+        Self { kind, done }
+    }
+
+    pub(crate) fn from_async_iterator(iterator: AsyncIterator) -> Self {
+        Self::with_kind(Kind::AsyncIterator(iterator))
+    }
+
+    pub(crate) fn from_sync_iterator(iterator: js_sys::Iterator) -> Self {
+        Self::with_kind(Kind::Iterator(iterator))
+    }
+
+    /// Obtain an iterator from `[Symbol.asyncIterator]()`, falling back to `[Symbol.iterator]()`,
+    /// as `for await` does.
+    pub(crate) fn from_async_iterable(iterable: &JsValue) -> Result<Self, JsValue> {
+        if !iterable.is_null() && !iterable.is_undefined() {
+            // NOTE: `Reflect.get` only accepts objects, so the methods of a primitive such as a
+            // string are looked up on its prototype
+            let target = if iterable.is_object() || iterable.is_function() {
+                iterable.clone()
+            } else {
+                Object::get_prototype_of(iterable).into()
+            };
+            let method = Reflect::get(&target, &Symbol::async_iterator())?;
+            if let Some(method) = method.dyn_ref::<Function>() {
+                let iterator = method.call0(iterable)?;
+                return Ok(Self::from_async_iterator(iterator.unchecked_into()));
+            }
+            let method = Reflect::get(&target, &Symbol::iterator())?;
+            if let Some(method) = method.dyn_ref::<Function>() {
+                let iterator = method.call0(iterable)?;
+                return Ok(Self::from_sync_iterator(iterator.unchecked_into()));
+            }
+        }
+        Err(js_sys::TypeError::new(
+            "Value is not async iterable: it has neither a [Symbol.asyncIterator] nor a [Symbol.iterator] method",
+        )
+        .into())
+    }
+
+    pub(crate) fn from_readable_stream(stream: &sys::ReadableStream) -> Result<Self, JsValue> {
+        Ok(Self::with_kind(Kind::Reader(stream.get_reader()?)))
+    }
+
+    pub(crate) fn from_byte_stream(stream: &sys::ReadableStream, buffer_size: u32) -> Result<Self, JsValue> {
+        // NOTE: a zero-length view is rejected by `read()`
+        let buffer_size = buffer_size.max(1);
+        let reader = stream.get_byob_reader()?;
+        let buffer = Some(ArrayBuffer::new(buffer_size));
+        Ok(Self::with_kind(Kind::ByobReader {
+            reader,
+            buffer,
+            buffer_size,
+        }))
+    }
+
+    pub(crate) fn next(&mut self) -> Result<Promise, JsValue> {
+        match &mut self.kind {
+            Kind::AsyncIterator(iterator) => iterator.next(),
+            Kind::Iterator(iterator) => Ok(sync_next(iterator)),
+            Kind::Reader(reader) => Ok(reader.read()),
+            Kind::ByobReader {
+                reader,
+                buffer,
+                buffer_size,
+            } => {
+                let buffer = buffer
+                    .take()
+                    .ok_or_else(|| js_sys::Error::new("ReadableStreamBYOBReader buffer has not been reclaimed"))?;
+                let view = Uint8Array::new_with_byte_offset_and_length(&buffer, 0, *buffer_size);
+                Ok(reader.read(&view))
+            },
+        }
+    }
+
+    /// Mark the source as exhausted, after it has produced `done: true` or failed.
+    pub(crate) fn finish(&mut self) {
+        self.done = true;
+    }
+
+    /// Cancel the source early, calling `return()` on an iterator or `cancel()` on a reader.
+    /// Returns `None` if the source is already finished or has no `return()` method.
+    pub(crate) fn cancel(&mut self) -> Result<Option<Promise>, JsValue> {
+        if self.done {
+            return Ok(None);
+        }
+        self.done = true;
+        let iterator: &JsValue = match &self.kind {
+            Kind::AsyncIterator(iterator) => iterator,
+            Kind::Iterator(iterator) => iterator,
+            Kind::Reader(reader) => return Ok(Some(reader.cancel())),
+            Kind::ByobReader { reader, .. } => return Ok(Some(reader.cancel())),
+        };
+        let method = Reflect::get(iterator, &"return".into())?;
+        if let Some(method) = method.dyn_ref::<Function>() {
+            let result = method.call0(iterator)?;
+            Ok(Some(Promise::resolve(&result)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// For BYOB readers, copy the filled part of a read result into `data` and reclaim the
+    /// (transferred) buffer for the next read. Returns `false` for other sources.
+    pub(crate) fn copy_filled(&mut self, value: &JsValue, data: &mut Vec<u8>) -> bool {
+        if let Kind::ByobReader { buffer, .. } = &mut self.kind {
+            let view = value.unchecked_ref::<Uint8Array>();
+            data.clear();
+            data.resize(view.length() as usize, 0);
+            view.copy_to(data);
+            *buffer = Some(view.buffer());
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Drop for Source {
+    fn drop(&mut self) {
+        // Readers are only released, leaving the stream available to other readers, while
+        // iterators are told to run their cleanup (e.g., `finally` blocks in generators).
+        if let Kind::AsyncIterator(_) | Kind::Iterator(_) = self.kind {
+            if let Ok(Some(promise)) = self.cancel() {
+                // NOTE: awaited so that a rejection is not reported as unhandled
+                wasm_bindgen_futures::spawn_local(async move {
+                    JsFuture::from(promise).await.ok();
+                });
+            }
+        }
+        // NOTE: older runtimes throw when releasing a reader with outstanding reads, in which case
+        // the lock is left to be released along with the reader
+        match &self.kind {
+            Kind::AsyncIterator(_) | Kind::Iterator(_) => {},
+            Kind::Reader(reader) => {
+                reader.release_lock().ok();
+            },
+            Kind::ByobReader { reader, .. } => {
+                reader.release_lock().ok();
+            },
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use futures_core::{Future, Stream};
-use js_sys::{AsyncIterator, IteratorNext};
+use crate::{source::Source, sys, FromJsValue, JsStreamError};
+use futures_core::{stream::FusedStream, Future, Stream};
+use js_sys::{AsyncIterator, IteratorNext, Promise};
 use std::{
+    collections::VecDeque,
     convert::TryFrom,
     pin::Pin,
     task::{Context, Poll},
@@ -8,39 +10,111 @@ use std::{
 use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_futures::JsFuture;
 
-pub struct JsStream<T: Unpin + JsCast> {
-    inner: AsyncIterator,
-    next: JsFuture,
-    phantom: std::marker::PhantomData<T>,
+pub struct JsStream<T: FromJsValue> {
+    inner: Source,
+    /// The pending results of the source, in order, which are cleared once the stream has
+    /// terminated.
+    next: VecDeque<JsFuture>,
+    /// How many results of the source to keep pending.
+    prefetch: usize,
+    phantom: std::marker::PhantomData<fn() -> T>,
 }
 
-impl<T: Unpin + JsCast> JsStream<T> {
-    /// The inner [`js_sys::AsyncIterator`] is expected to yield values of type `T`.
+impl<T: FromJsValue> JsStream<T> {
+    /// The inner [`js_sys::AsyncIterator`] is expected to yield values which decode as `T` through
+    /// [`FromJsValue`].
     pub fn new(inner: AsyncIterator) -> Result<Self, JsValue> {
-        let next = JsFuture::from(inner.next()?);
+        Self::with_source(Source::from_async_iterator(inner))
+    }
+
+    /// The iterable is iterated the way `for await` iterates it: through `[Symbol.asyncIterator]()`
+    /// or, failing that, `[Symbol.iterator]()`, e.g., a Node readable, a `ReadableStream` in newer
+    /// runtimes, an async generator object, an array, or a string. Values are decoded as `T`.
+    pub fn from_async_iterable(iterable: &JsValue) -> Result<Self, JsValue> {
+        Self::with_source(Source::from_async_iterable(iterable)?)
+    }
+
+    /// The inner [`js_sys::Iterator`] is stepped the way `for await` steps a sync iterable, so it
+    /// may yield promises, which are awaited before their values are decoded as `T`.
+    pub fn from_sync_iterator(inner: js_sys::Iterator) -> Result<Self, JsValue> {
+        Self::with_source(Source::from_sync_iterator(inner))
+    }
+
+    /// The [`ReadableStream`](sys::ReadableStream) is read through a default reader, which is
+    /// acquired here and released on drop. It is expected to yield values which decode as `T`.
+    pub fn from_readable_stream(stream: &sys::ReadableStream) -> Result<Self, JsValue> {
+        Self::with_source(Source::from_readable_stream(stream)?)
+    }
+
+    /// Cancel the source before it is exhausted and wait for it to clean up, by calling `return()`
+    /// on an async iterator or `cancel()` on a stream reader. Dropping calls `return()` on an async
+    /// iterator without waiting, and only releases a stream reader.
+    pub async fn cancel(mut self) -> Result<(), JsValue> {
+        if let Some(promise) = self.inner.cancel()? {
+            JsFuture::from(promise).await?;
+        }
+        Ok(())
+    }
+
+    /// Keep up to `depth` calls to `next()` (or `read()`) outstanding on the source, so that
+    /// latency-bound results overlap. Results are still yielded in order. Only use this with
+    /// sources which support concurrent `next()` calls, such as async generators and stream
+    /// readers. Defaults to `1`.
+    pub fn with_prefetch(mut self, depth: usize) -> Self {
+        self.prefetch = depth.max(1);
+        self
+    }
+
+    fn with_source(mut inner: Source) -> Result<Self, JsValue> {
+        let mut next = VecDeque::new();
+        next.push_back(JsFuture::from(inner.next()?));
+        let prefetch = 1;
         let phantom = std::marker::PhantomData;
-        Ok(Self { inner, next, phantom })
+        Ok(Self {
+            inner,
+            next,
+            prefetch,
+            phantom,
+        })
+    }
+
+    /// Request results from the source until `prefetch` are pending.
+    fn fill(&mut self) {
+        while self.next.len() < self.prefetch {
+            // NOTE: an error is queued behind the pending results so that it is yielded in order
+            let promise = self.inner.next().unwrap_or_else(|error| Promise::reject(&error));
+            self.next.push_back(JsFuture::from(promise));
+        }
+    }
+
+    /// Stop polling the source, after it has produced `done: true` or failed.
+    fn terminate(&mut self) {
+        self.next.clear();
+        self.inner.finish();
     }
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Result<Poll<Option<T>>, JsValue> {
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Result<Poll<Option<T>>, JsStreamError> {
         let this = self.get_mut();
-        let next = Pin::new(&mut this.next);
-        let status = next.poll(cx)?;
-        match status {
-            Poll::Ready(object) => {
+        if this.next.is_empty() {
+            return Ok(Poll::Ready(None));
+        }
+        this.fill();
+        let next = Pin::new(&mut this.next[0]);
+        match next.poll(cx) {
+            Poll::Ready(Err(error)) => {
+                this.terminate();
+                Err(error.into())
+            },
+            Poll::Ready(Ok(object)) => {
+                this.next.pop_front();
                 let iterator_next = object.unchecked_into::<IteratorNext>();
                 if iterator_next.done() {
+                    this.terminate();
                     Ok(Poll::Ready(None))
                 } else {
-                    let value = iterator_next.value().dyn_into::<T>()?;
-                    match this.inner.next() {
-                        Ok(promise) => {
-                            this.next = JsFuture::from(promise);
-                        },
-                        Err(error) => {
-                            return Err(error);
-                        },
-                    }
+                    this.fill();
+                    // NOTE: a value which fails to decode is not fatal, so the stream carries on
+                    let value = T::from_js_value(iterator_next.value())?;
                     Ok(Poll::Ready(Some(value)))
                 }
             },
@@ -49,7 +123,7 @@ impl<T: Unpin + JsCast> JsStream<T> {
     }
 }
 
-impl<T: Unpin + JsCast> TryFrom<AsyncIterator> for JsStream<T> {
+impl<T: FromJsValue> TryFrom<AsyncIterator> for JsStream<T> {
     type Error = JsValue;
 
     fn try_from(inner: AsyncIterator) -> Result<Self, JsValue> {
@@ -57,8 +131,8 @@ impl<T: Unpin + JsCast> TryFrom<AsyncIterator> for JsStream<T> {
     }
 }
 
-impl<T: Unpin + JsCast> Stream for JsStream<T> {
-    type Item = Result<T, JsValue>;
+impl<T: FromJsValue> Stream for JsStream<T> {
+    type Item = Result<T, JsStreamError>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
         match JsStream::poll_next(self, cx) {
@@ -67,3 +141,9 @@ impl<T: Unpin + JsCast> Stream for JsStream<T> {
         }
     }
 }
+
+impl<T: FromJsValue> FusedStream for JsStream<T> {
+    fn is_terminated(&self) -> bool {
+        self.next.is_empty()
+    }
+}
@@ -0,0 +1,178 @@
+//! Bindings for JS APIs which are not covered by [`js_sys`].
+
+use js_sys::{ArrayBuffer, Function, Object, Promise, Uint8Array};
+use wasm_bindgen::{prelude::*, JsCast};
+
+#[wasm_bindgen]
+extern {
+    /// Any [`js_sys::ArrayBuffer`] view, e.g., a typed array, a [`js_sys::DataView`], or a Node
+    /// `Buffer`.
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object, is_type_of = ArrayBuffer::is_view)]
+    pub type ArrayBufferView;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn buffer(this: &ArrayBufferView) -> JsValue;
+
+    #[wasm_bindgen(method, getter, js_name = byteOffset)]
+    pub fn byte_offset(this: &ArrayBufferView) -> u32;
+
+    #[wasm_bindgen(method, getter, js_name = byteLength)]
+    pub fn byte_length(this: &ArrayBufferView) -> u32;
+}
+
+#[wasm_bindgen]
+extern {
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object)]
+    pub type Blob;
+
+    #[wasm_bindgen(method, js_name = arrayBuffer)]
+    pub fn array_buffer(this: &Blob) -> Promise;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn size(this: &Blob) -> f64;
+
+    #[wasm_bindgen(method)]
+    pub fn slice(this: &Blob, start: f64, end: f64) -> Blob;
+}
+
+#[wasm_bindgen]
+extern {
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object)]
+    pub type EventTarget;
+
+    #[wasm_bindgen(catch, method, js_name = addEventListener)]
+    pub fn add_event_listener(this: &EventTarget, kind: &str, listener: &Function) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(catch, method, js_name = removeEventListener)]
+    pub fn remove_event_listener(this: &EventTarget, kind: &str, listener: &Function) -> Result<(), JsValue>;
+}
+
+#[wasm_bindgen]
+extern {
+    /// A Node `EventEmitter`, or any object with `on` and `removeListener` methods.
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object, is_type_of = is_event_emitter)]
+    pub type EventEmitter;
+
+    #[wasm_bindgen(catch, method)]
+    pub fn on(this: &EventEmitter, event: &str, listener: &Function) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, method, js_name = removeListener)]
+    pub fn remove_listener(this: &EventEmitter, event: &str, listener: &Function) -> Result<JsValue, JsValue>;
+}
+
+#[wasm_bindgen]
+extern {
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object)]
+    pub type WritableStream;
+
+    #[wasm_bindgen(catch, method, js_name = getWriter)]
+    pub fn get_writer(this: &WritableStream) -> Result<WritableStreamDefaultWriter, JsValue>;
+}
+
+#[wasm_bindgen]
+extern {
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object)]
+    pub type WritableStreamDefaultWriter;
+
+    #[wasm_bindgen(method)]
+    pub fn close(this: &WritableStreamDefaultWriter) -> Promise;
+
+    #[wasm_bindgen(method, js_name = releaseLock)]
+    pub fn release_lock(this: &WritableStreamDefaultWriter);
+
+    #[wasm_bindgen(method)]
+    pub fn write(this: &WritableStreamDefaultWriter, chunk: &JsValue) -> Promise;
+}
+
+#[wasm_bindgen]
+extern {
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object)]
+    pub type ReadableStream;
+
+    #[wasm_bindgen(catch, method, js_name = getReader)]
+    pub fn get_reader(this: &ReadableStream) -> Result<ReadableStreamDefaultReader, JsValue>;
+
+    #[wasm_bindgen(catch, method, js_name = getReader)]
+    pub fn get_reader_with_options(this: &ReadableStream, options: &Object) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn locked(this: &ReadableStream) -> bool;
+}
+
+#[wasm_bindgen]
+extern {
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object)]
+    pub type ReadableStreamDefaultReader;
+
+    #[wasm_bindgen(method)]
+    pub fn cancel(this: &ReadableStreamDefaultReader) -> Promise;
+
+    #[wasm_bindgen(method)]
+    pub fn read(this: &ReadableStreamDefaultReader) -> Promise;
+
+    #[wasm_bindgen(catch, method, js_name = releaseLock)]
+    pub fn release_lock(this: &ReadableStreamDefaultReader) -> Result<(), JsValue>;
+}
+
+#[wasm_bindgen]
+extern {
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object)]
+    pub type ReadableStreamDefaultController;
+
+    #[wasm_bindgen(catch, method)]
+    pub fn close(this: &ReadableStreamDefaultController) -> Result<(), JsValue>;
+
+    /// `None` once the stream has errored.
+    #[wasm_bindgen(method, getter, js_name = desiredSize)]
+    pub fn desired_size(this: &ReadableStreamDefaultController) -> Option<f64>;
+
+    #[wasm_bindgen(catch, method)]
+    pub fn enqueue(this: &ReadableStreamDefaultController, chunk: &JsValue) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method)]
+    pub fn error(this: &ReadableStreamDefaultController, error: &JsValue);
+}
+
+#[wasm_bindgen]
+extern {
+    #[derive(Clone, Debug)]
+    #[wasm_bindgen(extends = Object, js_name = ReadableStreamBYOBReader)]
+    pub type ReadableStreamByobReader;
+
+    #[wasm_bindgen(method)]
+    pub fn cancel(this: &ReadableStreamByobReader) -> Promise;
+
+    #[wasm_bindgen(method)]
+    pub fn read(this: &ReadableStreamByobReader, view: &Uint8Array) -> Promise;
+
+    #[wasm_bindgen(catch, method, js_name = releaseLock)]
+    pub fn release_lock(this: &ReadableStreamByobReader) -> Result<(), JsValue>;
+}
+
+// NOTE: `EventEmitter` is not a global, so it is recognized by its methods rather than with
+// `instanceof`
+fn is_event_emitter(value: &JsValue) -> bool {
+    ["on", "removeListener"].iter().all(|name| {
+        let method = js_sys::Reflect::get(value, &JsValue::from_str(name));
+        matches!(method, Ok(method) if method.is_function())
+    })
+}
+
+impl ReadableStream {
+    /// Acquire a `ReadableStreamBYOBReader`, failing if the stream is locked or is not a byte
+    /// stream.
+    pub fn get_byob_reader(&self) -> Result<ReadableStreamByobReader, JsValue> {
+        let options = Object::new();
+        js_sys::Reflect::set(&options, &"mode".into(), &"byob".into())?;
+        self.get_reader_with_options(&options).map(JsCast::unchecked_into)
+    }
+}
@@ -3,3 +3,148 @@ exports.createAsyncIterable = async function* (iterable) {
     yield elem;
   }
 };
+
+exports.createBlob = function (parts) {
+  return new Blob(parts);
+};
+
+exports.createCallbackSink = function (chunks) {
+  return async function (chunk) {
+    chunks.push(chunk);
+  };
+};
+
+exports.createWritableStream = function (chunks) {
+  return new WritableStream({
+    write(chunk) {
+      chunks.push(chunk);
+    },
+    close() {
+      chunks.push("closed");
+    },
+  });
+};
+
+// NOTE: chunks are enqueued as they are pulled, since erroring a stream discards its queue
+exports.createReadableStream = function (chunks, error) {
+  let index = 0;
+  return new ReadableStream({
+    pull(controller) {
+      if (index < chunks.length) {
+        controller.enqueue(chunks[index++]);
+      } else if (error === undefined) {
+        controller.close();
+      } else {
+        controller.error(error);
+      }
+    },
+  });
+};
+
+exports.createByteStream = function (chunks) {
+  return new ReadableStream({
+    type: "bytes",
+    start(controller) {
+      for (const chunk of chunks) {
+        controller.enqueue(chunk);
+      }
+      controller.close();
+    },
+  });
+};
+
+exports.createTrackedAsyncIterable = async function* (iterable, state) {
+  try {
+    for (const elem of iterable) {
+      yield elem;
+    }
+  } finally {
+    state.finalized = true;
+  }
+};
+
+exports.delay = function () {
+  return new Promise((resolve) => setTimeout(resolve, 0));
+};
+
+exports.createFailingAsyncIterable = async function* (name) {
+  const error = new Error("failed");
+  error.name = name;
+  throw error;
+};
+
+exports.createReusedBufferIterable = async function* () {
+  const buffer = new Uint8Array(2);
+  for (const chunk of [[1, 2], [3, 4], [5, 6]]) {
+    buffer.set(chunk);
+    yield buffer;
+  }
+};
+
+exports.collectAsyncIterable = async function (iterable) {
+  const values = [];
+  for await (const value of iterable) {
+    values.push(value);
+  }
+  return values;
+};
+
+exports.takeFirst = async function (iterable) {
+  for await (const value of iterable) {
+    return value;
+  }
+};
+
+exports.cancelStream = function (stream) {
+  return stream.cancel();
+};
+
+exports.createTrackedIterable = function* (iterable, state) {
+  try {
+    for (const elem of iterable) {
+      yield elem;
+    }
+  } finally {
+    state.finalized = true;
+  }
+};
+
+exports.createConcurrentIterator = function (count, state) {
+  state.calls = 0;
+  return {
+    next() {
+      const index = state.calls++;
+      const result = index < count ? { value: index, done: false } : { value: undefined, done: true };
+      // later calls resolve sooner, to check that results are still yielded in order
+      return new Promise((resolve) => setTimeout(() => resolve(result), count - index));
+    },
+  };
+};
+
+exports.createEventTarget = function () {
+  return new EventTarget();
+};
+
+exports.dispatchEvent = function (target, type) {
+  target.dispatchEvent(new Event(type));
+};
+
+exports.createEventEmitter = function () {
+  const { EventEmitter } = require("events");
+  return new EventEmitter();
+};
+
+exports.emit = function (emitter, event, value) {
+  emitter.emit(event, value);
+};
+
+exports.listenerCount = function (emitter, event) {
+  return emitter.listenerCount(event);
+};
+
+exports.pushAll = async function (push, values) {
+  for (const value of values) {
+    await push(value);
+  }
+  push.end();
+};
@@ -0,0 +1,75 @@
+use futures_util::stream::{self, StreamExt};
+use js_sys::*;
+use js_sys_futures::*;
+use std::{cell::Cell, rc::Rc};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_test::*;
+
+struct DropGuard(Rc<Cell<bool>>);
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn async_iterator_collect() {
+    async fn run() -> Result<(), JsValue> {
+        let vals = stream::iter(vec!["foo", "bar", "baz"]).map(|val| Ok(JsValue::from(val)));
+        let iter = stream_to_async_iterator(vals);
+
+        let array = JsFuture::from(super::collect_async_iterable(&iter)).await?;
+        let array = array.dyn_into::<Array>()?;
+        assert_eq!(3, array.length());
+        assert_eq!(Some("foo".to_string()), array.get(0).as_string());
+        assert_eq!(Some("baz".to_string()), array.get(2).as_string());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn async_iterator_error() {
+    async fn run() -> Result<(), JsValue> {
+        let dropped = Rc::new(Cell::new(false));
+        let guard = DropGuard(dropped.clone());
+        let vals = vec![Ok(JsValue::from("foo")), Err(JsValue::from("failed"))];
+        let vals = stream::iter(vals).map(move |val| {
+            let _ = &guard;
+            val
+        });
+        let iter = stream_to_async_iterator(vals);
+
+        let result = JsFuture::from(super::collect_async_iterable(&iter)).await;
+        assert_eq!(Some("failed".to_string()), result.unwrap_err().as_string());
+        assert!(dropped.get());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn async_iterator_break_drops_stream() {
+    async fn run() -> Result<(), JsValue> {
+        let dropped = Rc::new(Cell::new(false));
+        let guard = DropGuard(dropped.clone());
+        let vals = stream::iter(vec!["foo", "bar"]).map(move |val| {
+            let _ = &guard;
+            Ok(JsValue::from(val))
+        });
+        let iter = stream_to_async_iterator(vals);
+
+        let first = JsFuture::from(super::take_first(&iter)).await?;
+        assert_eq!(Some("foo".to_string()), first.as_string());
+        assert!(dropped.get());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
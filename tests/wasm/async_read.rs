@@ -1,4 +1,4 @@
-use futures_util::io::AsyncReadExt;
+use futures_util::io::{AsyncBufReadExt, AsyncReadExt};
 use js_sys::*;
 use js_sys_futures::*;
 use wasm_bindgen::prelude::*;
@@ -88,3 +88,411 @@ async fn read_string() {
 
     run().await.unwrap();
 }
+
+#[wasm_bindgen_test]
+async fn read_lines() {
+    async fn run() -> Result<(), Error> {
+        let vals: Vec<&str> = vec!["foo\nb", "ar\n", "baz"];
+        let vals = vals
+            .into_iter()
+            .map(|str| JsString::from(str).into())
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+
+        let mut reader = JsAsyncRead::new(iter)?;
+        let mut line = String::new();
+
+        let amt = reader.read_line(&mut line).await.unwrap();
+        assert_eq!(amt, 4);
+        assert_eq!(line, "foo\n");
+
+        line.clear();
+        let amt = reader.read_line(&mut line).await.unwrap();
+        assert_eq!(amt, 4);
+        assert_eq!(line, "bar\n");
+
+        line.clear();
+        let amt = reader.read_line(&mut line).await.unwrap();
+        assert_eq!(amt, 3);
+        assert_eq!(line, "baz");
+
+        line.clear();
+        let amt = reader.read_line(&mut line).await.unwrap();
+        assert_eq!(amt, 0);
+        assert_eq!(line, "");
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_uint8array_then_fill_buf() {
+    async fn run() -> Result<(), Error> {
+        let vals: Vec<&[u8]> = vec![&[1, 2, 3, 4], &[5]];
+        let vals = vals
+            .into_iter()
+            .map(|bytes| Uint8Array::from(bytes).into())
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+
+        let mut reader = JsAsyncRead::new(iter)?;
+        let mut out = [0u8; 2];
+
+        let amt = reader.read(&mut out).await.unwrap();
+        assert_eq!(&out[.. amt], [1, 2]);
+
+        let mut rest = vec![];
+        let amt = reader.read_until(5, &mut rest).await.unwrap();
+        assert_eq!(amt, 3);
+        assert_eq!(rest, [3, 4, 5]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_string_encodings() {
+    async fn read_all(string: &JsString, encoding: StringEncoding) -> std::io::Result<Vec<u8>> {
+        let vals = std::iter::once(JsValue::from(string)).collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+        let mut reader = JsAsyncRead::new(iter).unwrap().with_string_encoding(encoding);
+        let mut out = vec![];
+        reader.read_to_end(&mut out).await?;
+        Ok(out)
+    }
+
+    // "a", lone high surrogate, "é"
+    let lone = JsString::from_char_code(&[0x61, 0xd800, 0xe9]);
+    assert!(read_all(&lone, StringEncoding::Utf8).await.is_err());
+    assert_eq!(
+        read_all(&lone, StringEncoding::Utf8Lossy).await.unwrap(),
+        "a\u{fffd}é".as_bytes()
+    );
+    assert_eq!(read_all(&lone, StringEncoding::Wtf8).await.unwrap(), [
+        0x61, 0xed, 0xa0, 0x80, 0xc3, 0xa9
+    ]);
+    assert_eq!(read_all(&lone, StringEncoding::Utf16Le).await.unwrap(), [
+        0x61, 0x00, 0x00, 0xd8, 0xe9, 0x00
+    ]);
+    assert_eq!(read_all(&lone, StringEncoding::Utf16Be).await.unwrap(), [
+        0x00, 0x61, 0xd8, 0x00, 0x00, 0xe9
+    ]);
+    assert!(read_all(&lone, StringEncoding::Latin1).await.is_err());
+
+    let binary = JsString::from_char_code(&[0x00, 0x7f, 0xff]);
+    assert_eq!(read_all(&binary, StringEncoding::Latin1).await.unwrap(), [
+        0x00, 0x7f, 0xff
+    ]);
+}
+
+#[wasm_bindgen_test]
+async fn read_buffer_sources() {
+    async fn run() -> Result<(), Error> {
+        let bytes = Uint8Array::from(&[1u8, 2, 3, 4, 5, 6, 7, 8][..]);
+        let vals = Array::new();
+        // ArrayBuffer
+        vals.push(&Uint8Array::from(&[1u8, 2][..]).buffer());
+        // DataView over the middle of a larger buffer
+        vals.push(&DataView::new(&bytes.buffer(), 2, 2));
+        // Non-byte typed array
+        vals.push(&Uint16Array::new(&bytes.subarray(4, 6)));
+        // Blob
+        let parts = std::iter::once(JsValue::from(bytes.subarray(6, 8))).collect::<Array>();
+        vals.push(&super::create_blob(&parts));
+        let iter = super::create_async_iterable(&vals.values());
+
+        let mut reader = JsAsyncRead::new(iter)?;
+        let mut out = vec![];
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, [1, 2, 3, 4, 5, 0, 6, 0, 7, 8]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_readable_stream() {
+    async fn run() -> Result<(), Error> {
+        let vals: Vec<&[u8]> = vec![&[1, 2], &[3]];
+        let vals = vals
+            .into_iter()
+            .map(|bytes| Uint8Array::from(bytes).into())
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let readable = super::create_readable_stream(&vals, &JsValue::UNDEFINED);
+
+        let mut reader = JsAsyncRead::from_readable_stream(&readable)?;
+        let mut out = vec![];
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, [1, 2, 3]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_byte_stream() {
+    async fn run() -> Result<(), Error> {
+        let vals: Vec<&[u8]> = vec![&[1, 2, 3], &[4]];
+        let vals = vals
+            .into_iter()
+            .map(|bytes| Uint8Array::from(bytes).into())
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let readable = super::create_byte_stream(&vals);
+
+        let mut reader = JsAsyncRead::from_byte_stream(&readable, 2)?;
+        let mut out = vec![];
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, [1, 2, 3, 4]);
+
+        drop(reader);
+        assert!(!readable.locked());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_byte_stream_zero_buffer_size() {
+    async fn run() -> Result<(), Error> {
+        let vals = std::iter::once(JsValue::from(Uint8Array::from(&[1, 2][..]))).collect::<Array>();
+        let readable = super::create_byte_stream(&vals);
+
+        let mut reader = JsAsyncRead::from_byte_stream(&readable, 0)?;
+        let mut out = vec![];
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, [1, 2]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_error() {
+    async fn run() -> Result<(), Error> {
+        let vals = std::iter::once(JsValue::from(42)).collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+
+        let mut reader = JsAsyncRead::new(iter)?;
+        let mut out = [0u8; 1];
+        let error = reader.read(&mut out).await.unwrap_err();
+        let error = error.get_ref().unwrap().downcast_ref::<JsStreamError>().unwrap();
+        assert!(matches!(error, JsStreamError::Protocol(_)));
+        assert_eq!(error.name().as_deref(), Some("TypeError"));
+        assert!(error.message().is_some());
+        assert_eq!(reader.read(&mut out).await.unwrap(), 0);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_reused_buffer() {
+    async fn run() -> Result<(), Error> {
+        let mut reader = JsAsyncRead::new(super::create_reused_buffer_iterable())?;
+        let mut data = vec![];
+        reader.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, [1, 2, 3, 4, 5, 6]);
+
+        let mut reader = JsAsyncRead::new(super::create_reused_buffer_iterable())?;
+        let mut data = vec![];
+        let mut out = [0u8; 1];
+        while reader.read(&mut out).await.unwrap() > 0 {
+            data.extend_from_slice(&out);
+        }
+        assert_eq!(data, [1, 2, 3, 4, 5, 6]);
+
+        let mut reader = JsAsyncRead::new(super::create_reused_buffer_iterable())?;
+        let mut data = vec![];
+        reader.read_until(0, &mut data).await.unwrap();
+        assert_eq!(data, [1, 2, 3, 4, 5, 6]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_cancel() {
+    async fn run() -> Result<(), JsValue> {
+        let vals = vec!["foo", "bar", "baz"]
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let state = Object::new();
+        let iter = super::create_tracked_async_iterable(&vals.values(), &state);
+
+        let mut reader = JsAsyncRead::new(iter)?;
+        let mut out = [0u8; 3];
+        reader.read_exact(&mut out).await.unwrap();
+        assert_eq!(b"foo", &out);
+        assert!(Reflect::get(&state, &"finalized".into())?.is_undefined());
+
+        reader.cancel().await?;
+        assert_eq!(Reflect::get(&state, &"finalized".into())?.as_bool(), Some(true));
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_drop_returns() {
+    async fn run() -> Result<(), JsValue> {
+        let vals = vec!["foo", "bar", "baz"]
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let state = Object::new();
+        let iter = super::create_tracked_async_iterable(&vals.values(), &state);
+
+        let mut reader = JsAsyncRead::new(iter)?;
+        let mut out = [0u8; 1];
+        reader.read_exact(&mut out).await.unwrap();
+        assert_eq!(b"f", &out);
+
+        drop(reader);
+        JsFuture::from(super::delay()).await?;
+        assert_eq!(Reflect::get(&state, &"finalized".into())?.as_bool(), Some(true));
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_error_kind() {
+    async fn run() -> Result<(), Error> {
+        let mut out = [0u8; 1];
+
+        let iter = super::create_failing_async_iterable("AbortError");
+        let mut reader = JsAsyncRead::new(iter)?;
+        let error = reader.read(&mut out).await.unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::Interrupted);
+        // NOTE: the reader has ended, so a retry sees EOF rather than the same error forever
+        assert_eq!(reader.read(&mut out).await.unwrap(), 0);
+
+        let iter = super::create_failing_async_iterable("TimeoutError");
+        let mut reader = JsAsyncRead::new(iter)?;
+        let error = reader.read(&mut out).await.unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+
+        let iter = super::create_failing_async_iterable("CustomError");
+        let mut reader = JsAsyncRead::new(iter)?.with_error_classifier(|error| {
+            if error.name().as_deref() == Some("CustomError") {
+                std::io::ErrorKind::WouldBlock
+            } else {
+                error.io_error_kind()
+            }
+        });
+        let error = reader.read(&mut out).await.unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::WouldBlock);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_resolved_chunk_without_extra_poll() {
+    async fn run() -> Result<(), JsValue> {
+        use futures_util::{io::AsyncRead, task::noop_waker};
+        use std::{pin::Pin, task::Context};
+
+        let vals = std::iter::once(JsValue::from(Uint8Array::from(&[1u8, 2][..]))).collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+        let mut reader = JsAsyncRead::new(iter)?;
+
+        // let the pending `next()` promise settle before polling
+        JsFuture::from(super::delay()).await?;
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut out = [0u8; 2];
+        let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut out);
+        assert!(matches!(poll, std::task::Poll::Ready(Ok(2))));
+        assert_eq!(out, [1, 2]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_sync_iterator() {
+    async fn run() -> Result<(), Error> {
+        let vals = vec![
+            JsValue::from("foo"),
+            Promise::resolve(&Uint8Array::from(&b"bar"[..]).into()).into(),
+        ];
+        let vals = vals.into_iter().collect::<Array>();
+
+        let mut reader = JsAsyncRead::from_sync_iterator(vals.values())?;
+        let mut out = vec![];
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(b"foobar".to_vec(), out);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_async_iterable() {
+    async fn run() -> Result<(), Error> {
+        let vals = vec![JsValue::from("foo"), JsValue::from("bar")];
+        let vals = vals.into_iter().collect::<Array>();
+
+        let mut reader = JsAsyncRead::from_async_iterable(&vals)?;
+        let mut out = vec![];
+        reader.read_to_end(&mut out).await.unwrap();
+        assert_eq!(b"foobar".to_vec(), out);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn read_after_eof() {
+    async fn run() -> Result<(), Error> {
+        let vals = std::iter::once(JsValue::from("foo")).collect::<Array>();
+
+        let mut reader = JsAsyncRead::from_sync_iterator(vals.values())?;
+        let mut out = [0u8; 4];
+        assert_eq!(reader.read(&mut out).await.unwrap(), 3);
+        assert_eq!(reader.read(&mut out).await.unwrap(), 0);
+        assert_eq!(reader.read(&mut out).await.unwrap(), 0);
+        assert!(reader.fill_buf().await.unwrap().is_empty());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
@@ -0,0 +1,50 @@
+use futures_util::io::AsyncWriteExt;
+use js_sys::*;
+use js_sys_futures::*;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+async fn write_callback() {
+    async fn run() -> Result<(), Error> {
+        let chunks = Array::new();
+        let mut writer = JsAsyncWrite::from_callback(super::create_callback_sink(&chunks));
+
+        writer.write_all(&[1, 2]).await.unwrap();
+        writer.write_all(&[3]).await.unwrap();
+        writer.close().await.unwrap();
+
+        assert_eq!(chunks.length(), 2);
+        assert_eq!(chunks.get(0).unchecked_into::<Uint8Array>().to_vec(), [1, 2]);
+        assert_eq!(chunks.get(1).unchecked_into::<Uint8Array>().to_vec(), [3]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn write_writable_stream() {
+    async fn run() -> Result<(), JsValue> {
+        let chunks = Array::new();
+        let stream = super::create_writable_stream(&chunks);
+        let mut writer = JsAsyncWrite::from_writable_stream(&stream)?;
+
+        writer.write_all(b"foo").await.unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(chunks.length(), 1);
+
+        writer.write_all(b"bar").await.unwrap();
+        writer.close().await.unwrap();
+
+        assert_eq!(chunks.length(), 3);
+        assert_eq!(chunks.get(0).unchecked_into::<Uint8Array>().to_vec(), b"foo");
+        assert_eq!(chunks.get(1).unchecked_into::<Uint8Array>().to_vec(), b"bar");
+        assert_eq!(chunks.get(2).as_string().as_deref(), Some("closed"));
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
@@ -0,0 +1,59 @@
+use futures_util::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, SeekFrom};
+use js_sys::*;
+use js_sys_futures::*;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_test::*;
+
+fn create_blob(data: &[u8]) -> sys::Blob {
+    let parts = std::iter::once(JsValue::from(Uint8Array::from(data))).collect::<Array>();
+    super::create_blob(&parts).unchecked_into()
+}
+
+#[wasm_bindgen_test]
+async fn blob_reader() {
+    async fn run() -> std::io::Result<()> {
+        let data = (0 .. 100).collect::<Vec<u8>>();
+        let mut reader = JsBlobReader::new(&create_blob(&data)).with_block_size(16);
+        assert_eq!(100, reader.size());
+
+        let mut out = vec![];
+        reader.read_to_end(&mut out).await?;
+        assert_eq!(data, out);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn blob_reader_seek() {
+    async fn run() -> std::io::Result<()> {
+        let data = (0 .. 100).collect::<Vec<u8>>();
+        let mut reader = JsBlobReader::new(&create_blob(&data))
+            .with_block_size(16)
+            .with_cache_size(1);
+
+        assert_eq!(96, reader.seek(SeekFrom::End(-4)).await?);
+        let mut out = [0u8; 4];
+        reader.read_exact(&mut out).await?;
+        assert_eq!([96, 97, 98, 99], out);
+
+        assert_eq!(10, reader.seek(SeekFrom::Start(10)).await?);
+        assert_eq!(&data[10 .. 16], reader.fill_buf().await?);
+        reader.consume_unpin(4);
+        assert_eq!(20, reader.seek(SeekFrom::Current(6)).await?);
+        reader.read_exact(&mut out).await?;
+        assert_eq!([20, 21, 22, 23], out);
+
+        assert_eq!(200, reader.seek(SeekFrom::Start(200)).await?);
+        assert_eq!(0, reader.read(&mut out).await?);
+
+        let error = reader.seek(SeekFrom::Current(-300)).await.unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidInput, error.kind());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
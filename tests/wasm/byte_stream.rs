@@ -0,0 +1,64 @@
+use futures_util::stream::TryStreamExt;
+use js_sys::*;
+use js_sys_futures::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+async fn byte_stream() {
+    async fn run() -> Result<(), JsStreamError> {
+        let blob_parts = std::iter::once(JsValue::from("baz")).collect::<Array>();
+        let vals = vec![
+            Uint8Array::from(&[1, 2][..]).into(),
+            JsValue::from("foo"),
+            ArrayBuffer::new(0).into(),
+            super::create_blob(&blob_parts),
+        ];
+        let vals = vals.into_iter().collect::<Array>();
+
+        let stream = JsByteStream::from_sync_iterator(vals.values())?;
+        let chunks = stream.try_collect::<Vec<_>>().await?;
+        assert_eq!(4, chunks.len());
+        assert_eq!(&[1, 2][..], &chunks[0][..]);
+        assert_eq!(&b"foo"[..], &chunks[1][..]);
+        assert!(chunks[2].is_empty());
+        assert_eq!(&b"baz"[..], &chunks[3][..]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn byte_stream_string_encoding() {
+    async fn run() -> Result<(), JsStreamError> {
+        let vals = std::iter::once(JsValue::from("hi")).collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+
+        let mut stream = JsByteStream::new(iter)?.with_string_encoding(StringEncoding::Utf16Le);
+        let chunk = stream.try_next().await?.unwrap();
+        assert_eq!(&[b'h', 0, b'i', 0][..], &chunk[..]);
+        assert!(stream.try_next().await?.is_none());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn byte_stream_error() {
+    async fn run() -> Result<(), JsStreamError> {
+        let vals = vec![JsValue::from(42), JsValue::from("foo")];
+        let vals = vals.into_iter().collect::<Array>();
+
+        let mut stream = JsByteStream::from_sync_iterator(vals.values())?;
+        assert!(matches!(stream.try_next().await, Err(JsStreamError::Protocol(_))));
+        assert_eq!(&b"foo"[..], &stream.try_next().await?.unwrap()[..]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
@@ -0,0 +1,45 @@
+use futures_util::stream::StreamExt;
+use js_sys::*;
+use js_sys_futures::*;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+fn point(x: &JsValue, y: &JsValue) -> Result<JsValue, JsValue> {
+    let object = Object::new();
+    Reflect::set(&object, &"x".into(), x)?;
+    Reflect::set(&object, &"y".into(), y)?;
+    Ok(object.into())
+}
+
+#[wasm_bindgen_test]
+async fn deserialize_stream() {
+    async fn run() -> Result<(), JsValue> {
+        let vals = vec![
+            point(&1.into(), &2.into())?,
+            point(&"foo".into(), &2.into())?,
+            point(&3.into(), &4.into())?,
+        ];
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+
+        let mut stream = JsDeserializeStream::<Point>::new(iter)?;
+        assert_eq!(stream.next().await.unwrap()?, Point { x: 1.0, y: 2.0 });
+        match stream.next().await.unwrap() {
+            Err(JsStreamError::Deserialize { index, .. }) => assert_eq!(index, 1),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(stream.next().await.unwrap()?, Point { x: 3.0, y: 4.0 });
+        assert!(stream.next().await.is_none());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
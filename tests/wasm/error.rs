@@ -0,0 +1,20 @@
+use js_sys::*;
+use js_sys_futures::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn error_into_js_error() {
+    let original = TypeError::new("boom");
+    let error = Error::from(JsStreamError::Rejected(original.clone().into()));
+    assert_eq!(JsValue::from(error), JsValue::from(original));
+
+    let error = Error::from(JsStreamError::Rejected("failed".into()));
+    assert_eq!(String::from(error.message()), "failed");
+    let cause = Reflect::get(&error, &"cause".into()).unwrap();
+    assert_eq!(cause.as_string().as_deref(), Some("failed"));
+
+    let error = Error::from(JsStreamError::type_cast::<String>(Error::new("value").into()));
+    assert!(String::from(error.message()).starts_with("expected a value of type"));
+    assert!(Reflect::get(&error, &"cause".into()).unwrap().is_object());
+}
@@ -0,0 +1,88 @@
+use futures_util::stream::{StreamExt, TryStreamExt};
+use js_sys::*;
+use js_sys_futures::*;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+async fn event_stream_event_target() {
+    async fn run() -> Result<(), JsStreamError> {
+        let target = super::create_event_target();
+        let mut stream = JsEventStream::<Object>::from_event_target(&target, "foo")?;
+
+        super::dispatch_event(&target, "foo");
+        super::dispatch_event(&target, "bar");
+        super::dispatch_event(&target, "foo");
+
+        let event = stream.try_next().await?.unwrap();
+        assert_eq!(
+            Reflect::get(&event, &"type".into())?.as_string().as_deref(),
+            Some("foo")
+        );
+        assert!(stream.try_next().await?.is_some());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn event_stream_event_emitter() {
+    async fn run() -> Result<(), JsStreamError> {
+        let emitter = super::create_event_emitter();
+        let mut stream = JsEventStream::<String>::from_event_emitter(&emitter, "data")?;
+        assert_eq!(1, super::listener_count(&emitter, "data"));
+
+        super::emit(&emitter, "data", &"foo".into());
+        super::emit(&emitter, "data", &"bar".into());
+        assert_eq!(Some("foo".to_string()), stream.try_next().await?);
+        assert_eq!(Some("bar".to_string()), stream.try_next().await?);
+
+        drop(stream);
+        assert_eq!(0, super::listener_count(&emitter, "data"));
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+fn event_emitter_cast() {
+    let emitter = JsValue::from(super::create_event_emitter());
+    assert!(emitter.dyn_into::<sys::EventEmitter>().is_ok());
+    assert!(JsValue::from(Object::new()).dyn_into::<sys::EventEmitter>().is_err());
+    assert!(JsValue::from("on").dyn_into::<sys::EventEmitter>().is_err());
+}
+
+#[wasm_bindgen_test]
+async fn event_stream_overflow() {
+    async fn run() -> Result<(), JsStreamError> {
+        let emitter = super::create_event_emitter();
+        let policies = vec![
+            (OverflowPolicy::DropNewest, vec![0, 1]),
+            (OverflowPolicy::DropOldest, vec![2, 3]),
+        ];
+        for (policy, expected) in policies {
+            let mut stream = JsEventStream::<u32>::from_event_emitter(&emitter, "data")?.with_capacity(2, policy);
+            for value in 0 .. 4 {
+                super::emit(&emitter, "data", &value.into());
+            }
+            let values = (&mut stream).take(2).try_collect::<Vec<_>>().await?;
+            assert_eq!(expected, values);
+        }
+
+        let stream = JsEventStream::<u32>::from_event_emitter(&emitter, "data")?;
+        let mut stream = stream.with_capacity(1, OverflowPolicy::Fail);
+        super::emit(&emitter, "data", &0.into());
+        super::emit(&emitter, "data", &1.into());
+        assert_eq!(Some(0), stream.try_next().await?);
+        assert!(stream.try_next().await.is_err());
+        assert!(stream.next().await.is_none());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
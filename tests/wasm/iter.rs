@@ -0,0 +1,75 @@
+use js_sys::*;
+use js_sys_futures::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn iter() {
+    fn run() -> Result<(), JsStreamError> {
+        let vals = vec!["foo", "bar", "baz"]
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+
+        let vals = JsIter::<String>::new(vals.values()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(vec!["foo", "bar", "baz"], vals);
+
+        Ok(())
+    }
+
+    run().unwrap();
+}
+
+#[wasm_bindgen_test]
+fn iter_map_entries() {
+    fn run() -> Result<(), JsStreamError> {
+        let map = Map::new();
+        map.set(&"foo".into(), &1.into());
+        map.set(&"bar".into(), &2.into());
+
+        let mut iter = JsIter::<Array>::new(map.entries());
+        let entry = iter.next().unwrap()?;
+        assert_eq!(entry.get(0).as_string(), Some("foo".to_string()));
+        assert_eq!(entry.get(1).as_f64(), Some(1.0));
+        assert!(iter.next().is_some());
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+
+        Ok(())
+    }
+
+    run().unwrap();
+}
+
+#[wasm_bindgen_test]
+fn iter_type_cast_error() {
+    let vals = vec![JsValue::from(42), JsValue::from("foo")];
+    let vals = vals.into_iter().collect::<Array>();
+
+    let mut iter = JsIter::<String>::new(vals.values());
+    match iter.next() {
+        Some(Err(JsStreamError::TypeCast { .. })) => {},
+        _ => panic!("expected a type cast error"),
+    }
+    assert_eq!(iter.next().unwrap().unwrap(), "foo");
+}
+
+#[wasm_bindgen_test]
+fn iter_drop_returns() {
+    fn run() -> Result<(), JsValue> {
+        let vals = vec![JsValue::from("foo"), JsValue::from("bar")];
+        let vals = vals.into_iter().collect::<Array>();
+        let state = Object::new();
+
+        let mut iter = JsIter::<String>::new(super::create_tracked_iterable(&vals.values(), &state));
+        assert_eq!(iter.next().unwrap()?, "foo");
+
+        drop(iter);
+        assert_eq!(Reflect::get(&state, &"finalized".into())?.as_bool(), Some(true));
+
+        Ok(())
+    }
+
+    run().unwrap();
+}
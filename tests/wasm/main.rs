@@ -1,10 +1,80 @@
 use wasm_bindgen::prelude::*;
 
+mod async_iterator;
 mod async_read;
+mod async_write;
+mod blob_reader;
+mod byte_stream;
+#[cfg(feature = "serde")]
+mod deserialize;
+mod error;
+mod event_stream;
+mod iter;
+mod promise;
+mod push_stream;
+mod readable_stream;
 mod stream;
 
 #[wasm_bindgen(module = "tests/wasm/async_iterable.js")]
 extern {
     #[wasm_bindgen(js_name = createAsyncIterable)]
     fn create_async_iterable(iterable: &js_sys::Iterator) -> js_sys::AsyncIterator;
+
+    #[wasm_bindgen(js_name = createBlob)]
+    fn create_blob(parts: &js_sys::Array) -> JsValue;
+
+    #[wasm_bindgen(js_name = createCallbackSink)]
+    fn create_callback_sink(chunks: &js_sys::Array) -> js_sys::Function;
+
+    #[wasm_bindgen(js_name = createWritableStream)]
+    fn create_writable_stream(chunks: &js_sys::Array) -> js_sys_futures::sys::WritableStream;
+
+    #[wasm_bindgen(js_name = createReadableStream)]
+    fn create_readable_stream(chunks: &js_sys::Array, error: &JsValue) -> js_sys_futures::sys::ReadableStream;
+
+    #[wasm_bindgen(js_name = createByteStream)]
+    fn create_byte_stream(chunks: &js_sys::Array) -> js_sys_futures::sys::ReadableStream;
+
+    #[wasm_bindgen(js_name = createTrackedAsyncIterable)]
+    fn create_tracked_async_iterable(iterable: &js_sys::Iterator, state: &js_sys::Object) -> js_sys::AsyncIterator;
+
+    #[wasm_bindgen(js_name = createTrackedIterable)]
+    fn create_tracked_iterable(iterable: &js_sys::Iterator, state: &js_sys::Object) -> js_sys::Iterator;
+
+    fn delay() -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = createConcurrentIterator)]
+    fn create_concurrent_iterator(count: u32, state: &js_sys::Object) -> js_sys::AsyncIterator;
+
+    #[wasm_bindgen(js_name = createFailingAsyncIterable)]
+    fn create_failing_async_iterable(name: &str) -> js_sys::AsyncIterator;
+
+    #[wasm_bindgen(js_name = createReusedBufferIterable)]
+    fn create_reused_buffer_iterable() -> js_sys::AsyncIterator;
+
+    #[wasm_bindgen(js_name = collectAsyncIterable)]
+    fn collect_async_iterable(iterable: &js_sys::AsyncIterator) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = takeFirst)]
+    fn take_first(iterable: &js_sys::AsyncIterator) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = cancelStream)]
+    fn cancel_stream(stream: &js_sys_futures::sys::ReadableStream) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = createEventTarget)]
+    fn create_event_target() -> js_sys_futures::sys::EventTarget;
+
+    #[wasm_bindgen(js_name = dispatchEvent)]
+    fn dispatch_event(target: &js_sys_futures::sys::EventTarget, kind: &str);
+
+    #[wasm_bindgen(js_name = createEventEmitter)]
+    fn create_event_emitter() -> js_sys_futures::sys::EventEmitter;
+
+    fn emit(emitter: &js_sys_futures::sys::EventEmitter, event: &str, value: &JsValue);
+
+    #[wasm_bindgen(js_name = listenerCount)]
+    fn listener_count(emitter: &js_sys_futures::sys::EventEmitter, event: &str) -> u32;
+
+    #[wasm_bindgen(js_name = pushAll)]
+    fn push_all(push: &js_sys::Function, values: &js_sys::Array) -> js_sys::Promise;
 }
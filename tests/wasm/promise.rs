@@ -0,0 +1,57 @@
+use js_sys::*;
+use js_sys_futures::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+async fn promise() {
+    async fn run() -> Result<(), JsStreamError> {
+        let value = JsPromise::<String>::new(Promise::resolve(&"foo".into())).await?;
+        assert_eq!("foo", value);
+
+        let value = JsPromise::<Option<u32>>::from(Promise::resolve(&JsValue::NULL)).await?;
+        assert_eq!(None, value);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn promise_errors() {
+    match JsPromise::<String>::new(Promise::reject(&"failed".into())).await {
+        Err(JsStreamError::Rejected(value)) => assert_eq!(value.as_string().as_deref(), Some("failed")),
+        result => panic!("unexpected result: {:?}", result),
+    }
+    match JsPromise::<String>::new(Promise::resolve(&42.into())).await {
+        Err(JsStreamError::TypeCast { value, .. }) => assert_eq!(value.as_f64(), Some(42.0)),
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn promise_combinators() {
+    async fn run() -> Result<(), JsStreamError> {
+        let values = vec![Promise::resolve(&1.into()), Promise::resolve(&2.into())];
+        assert_eq!(vec![1, 2], promise_all::<u8, _>(values).await?);
+
+        let values = vec![Promise::resolve(&1.into()), Promise::reject(&"failed".into())];
+        assert!(promise_all::<u8, _>(values).await.is_err());
+
+        let values = vec![Promise::resolve(&1.into()), Promise::reject(&"failed".into())];
+        let settled = promise_all_settled::<u8, _>(values).await?;
+        assert_eq!(1, *settled[0].as_ref().unwrap());
+        assert!(matches!(settled[1], Err(JsStreamError::Rejected(_))));
+
+        let values = vec![Promise::new(&mut |_, _| {}), Promise::resolve(&"foo".into())];
+        assert_eq!("foo", promise_race::<String, _>(values).await?);
+
+        let values = vec![Promise::reject(&"failed".into()), Promise::resolve(&"foo".into())];
+        assert_eq!("foo", promise_any::<String, _>(values).await?);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
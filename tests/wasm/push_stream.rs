@@ -0,0 +1,85 @@
+use futures_core::stream::FusedStream;
+use futures_util::stream::{StreamExt, TryStreamExt};
+use js_sys::*;
+use js_sys_futures::*;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+async fn push_stream() {
+    async fn run() -> Result<(), JsStreamError> {
+        let (stream, push) = JsPushStream::<String>::new(1);
+        let vals = vec![JsValue::from("foo"), JsValue::from("bar"), JsValue::from("baz")];
+        let vals = vals.into_iter().collect::<Array>();
+        let pushed = JsPromise::<JsValue>::new(super::push_all(&push, &vals));
+
+        let vals = stream.try_collect::<Vec<_>>().await?;
+        assert_eq!(vec!["foo", "bar", "baz"], vals);
+        pushed.await?;
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn push_stream_backpressure() {
+    async fn run() -> Result<(), JsStreamError> {
+        let (mut stream, push) = JsPushStream::<u32>::new(2);
+
+        assert!(push.call1(&JsValue::NULL, &1.into())?.is_undefined());
+        let pending = push.call1(&JsValue::NULL, &2.into())?.dyn_into::<Promise>()?;
+
+        assert_eq!(Some(1), stream.try_next().await?);
+        JsPromise::<JsValue>::new(pending).await?;
+        assert_eq!(Some(2), stream.try_next().await?);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn push_stream_error() {
+    async fn run() -> Result<(), JsStreamError> {
+        let (mut stream, push) = JsPushStream::<u32>::new(4);
+        let error = Reflect::get(&push, &"error".into())?.dyn_into::<Function>()?;
+
+        push.call1(&JsValue::NULL, &1.into())?;
+        error.call1(&JsValue::NULL, &"failed".into())?;
+        assert_eq!(Some(1), stream.try_next().await?);
+        match stream.next().await {
+            Some(Err(JsStreamError::Rejected(value))) => assert_eq!(value.as_string().as_deref(), Some("failed")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(stream.next().await.is_none());
+        assert!(stream.is_terminated());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn push_stream_drop() {
+    async fn run() -> Result<(), JsStreamError> {
+        let (stream, push) = JsPushStream::<u32>::new(1);
+        let end = Reflect::get(&push, &"end".into())?.dyn_into::<Function>()?;
+        let error = Reflect::get(&push, &"error".into())?.dyn_into::<Function>()?;
+
+        let pending = push.call1(&JsValue::NULL, &1.into())?.dyn_into::<Promise>()?;
+        drop(stream);
+        JsPromise::<JsValue>::new(pending).await?;
+
+        assert!(push.call1(&JsValue::NULL, &2.into())?.is_undefined());
+        end.call0(&JsValue::NULL)?;
+        error.call1(&JsValue::NULL, &"failed".into())?;
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
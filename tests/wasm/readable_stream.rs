@@ -0,0 +1,194 @@
+use futures_util::{
+    io::{self, AsyncRead, AsyncReadExt},
+    stream::{self, StreamExt, TryStreamExt},
+};
+use js_sys_futures::*;
+use std::{
+    cell::Cell,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+/// Counts the reads made on the inner reader and records when it is dropped.
+struct TrackedReader {
+    inner: io::Cursor<Vec<u8>>,
+    reads: Rc<Cell<usize>>,
+    dropped: Rc<Cell<bool>>,
+}
+
+impl AsyncRead for TrackedReader {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        this.reads.set(this.reads.get() + 1);
+        Pin::new(&mut this.inner).poll_read(cx, buf)
+    }
+}
+
+impl Drop for TrackedReader {
+    fn drop(&mut self) {
+        self.dropped.set(true);
+    }
+}
+
+fn tracked_reader(data: &[u8]) -> (TrackedReader, Rc<Cell<usize>>, Rc<Cell<bool>>) {
+    let reads = Rc::new(Cell::new(0));
+    let dropped = Rc::new(Cell::new(false));
+    let reader = TrackedReader {
+        inner: io::Cursor::new(data.to_vec()),
+        reads: reads.clone(),
+        dropped: dropped.clone(),
+    };
+    (reader, reads, dropped)
+}
+
+#[wasm_bindgen_test]
+async fn readable_stream_round_trip() {
+    async fn run() -> Result<(), JsValue> {
+        let (reader, _, dropped) = tracked_reader(b"hello, world");
+        let readable = async_read_to_readable_stream(reader, 5, 16.0);
+
+        let mut reader = JsAsyncRead::from_readable_stream(&readable)?;
+        let mut data = vec![];
+        reader.read_to_end(&mut data).await.unwrap();
+        assert_eq!(b"hello, world".to_vec(), data);
+        assert!(dropped.get());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn readable_stream_backpressure() {
+    async fn run() -> Result<(), JsValue> {
+        let (reader, reads, _) = tracked_reader(&[0; 64]);
+        let readable = async_read_to_readable_stream(reader, 2, 4.0);
+
+        wasm_bindgen_futures::JsFuture::from(super::delay()).await?;
+        assert_eq!(2, reads.get());
+
+        let mut reader = JsAsyncRead::from_readable_stream(&readable)?;
+        let mut data = [0; 2];
+        reader.read_exact(&mut data).await.unwrap();
+        wasm_bindgen_futures::JsFuture::from(super::delay()).await?;
+        assert_eq!(3, reads.get());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn readable_stream_cancel() {
+    async fn run() -> Result<(), JsValue> {
+        let (reader, _, dropped) = tracked_reader(&[0; 64]);
+        let readable = async_read_to_readable_stream(reader, 2, 4.0);
+
+        wasm_bindgen_futures::JsFuture::from(super::cancel_stream(&readable)).await?;
+        assert!(dropped.get());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_readable_stream_round_trip() {
+    async fn run() -> Result<(), JsValue> {
+        let items = stream::iter(vec![Ok::<_, JsValue>("foo"), Ok("bar")]);
+        let readable = stream_to_readable_stream(items, 1.0);
+
+        let values = JsStream::<String>::from_readable_stream(&readable)?
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(vec!["foo".to_string(), "bar".to_string()], values);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_readable_stream_error() {
+    async fn run() -> Result<(), JsValue> {
+        let held = Rc::new(());
+        let items = stream::iter(vec![Ok("foo"), Err("failed")]).map({
+            let held = held.clone();
+            move |item| {
+                let _ = &held;
+                item
+            }
+        });
+        let readable = stream_to_readable_stream(items, 1.0);
+
+        let mut stream = JsStream::<String>::from_readable_stream(&readable)?;
+        assert_eq!(Some("foo".to_string()), stream.try_next().await?);
+        let error = stream.try_next().await.unwrap_err();
+        assert_eq!(Some("failed".to_string()), error.message());
+        assert_eq!(1, Rc::strong_count(&held));
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_readable_stream_zero_high_water_mark() {
+    async fn run() -> Result<(), JsValue> {
+        let pulled = Rc::new(Cell::new(0));
+        let items = stream::iter(vec!["foo", "bar"]).map({
+            let pulled = pulled.clone();
+            move |item| {
+                pulled.set(pulled.get() + 1);
+                Ok::<_, JsValue>(item)
+            }
+        });
+        let readable = stream_to_readable_stream(items, 0.0);
+
+        wasm_bindgen_futures::JsFuture::from(super::delay()).await?;
+        assert_eq!(0, pulled.get());
+
+        let mut stream = JsStream::<String>::from_readable_stream(&readable)?;
+        assert_eq!(Some("foo".to_string()), stream.try_next().await?);
+        assert_eq!(Some("bar".to_string()), stream.try_next().await?);
+        assert_eq!(None, stream.try_next().await?);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_readable_stream_backpressure() {
+    async fn run() -> Result<(), JsValue> {
+        let pulled = Rc::new(Cell::new(0));
+        let items = stream::iter(0 .. 10).map({
+            let pulled = pulled.clone();
+            move |item| {
+                pulled.set(pulled.get() + 1);
+                Ok::<_, JsValue>(item)
+            }
+        });
+        let readable = stream_to_readable_stream(items, 2.0);
+
+        wasm_bindgen_futures::JsFuture::from(super::delay()).await?;
+        assert_eq!(2, pulled.get());
+
+        wasm_bindgen_futures::JsFuture::from(super::cancel_stream(&readable)).await?;
+        wasm_bindgen_futures::JsFuture::from(super::delay()).await?;
+        assert_eq!(2, pulled.get());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
@@ -1,7 +1,8 @@
-use futures_util::stream::StreamExt;
+use futures_core::stream::FusedStream;
+use futures_util::stream::{StreamExt, TryStreamExt};
 use js_sys::*;
 use js_sys_futures::*;
-use wasm_bindgen::prelude::*;
+use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_test::*;
 
 #[wasm_bindgen_test]
@@ -31,3 +32,285 @@ async fn stream() {
 
     run().await.unwrap();
 }
+
+#[wasm_bindgen_test]
+async fn stream_readable_stream() {
+    async fn run() -> Result<(), Error> {
+        let vals = vec!["foo", "bar"].into_iter().map(Into::into).collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let readable = super::create_readable_stream(&vals, &JsValue::UNDEFINED);
+
+        let mut stream = JsStream::<JsString>::from_readable_stream(&readable)?;
+        assert!(readable.locked());
+
+        assert_eq!(JsString::from("foo"), stream.next().await.unwrap()?);
+        assert_eq!(JsString::from("bar"), stream.next().await.unwrap()?);
+        assert!(stream.next().await.is_none());
+
+        drop(stream);
+        assert!(!readable.locked());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_readable_stream_error() {
+    async fn run() -> Result<(), Error> {
+        let vals = std::iter::once(JsValue::from("foo")).collect::<Array>();
+        let readable = super::create_readable_stream(&vals, &Error::new("boom"));
+
+        let mut stream = JsStream::<JsString>::from_readable_stream(&readable)?;
+        assert_eq!(JsString::from("foo"), stream.next().await.unwrap()?);
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.is_terminated());
+        assert!(stream.next().await.is_none());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_cancel() {
+    async fn run() -> Result<(), JsValue> {
+        let vals = vec!["foo", "bar", "baz"]
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let state = Object::new();
+        let iter = super::create_tracked_async_iterable(&vals.values(), &state);
+
+        let mut stream = JsStream::<JsString>::new(iter)?;
+        assert_eq!(JsString::from("foo"), stream.next().await.unwrap()?);
+        assert!(Reflect::get(&state, &"finalized".into())?.is_undefined());
+
+        stream.cancel().await?;
+        assert_eq!(Reflect::get(&state, &"finalized".into())?.as_bool(), Some(true));
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_drop_returns() {
+    async fn run() -> Result<(), JsValue> {
+        let vals = vec!["foo", "bar", "baz"]
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let state = Object::new();
+        let iter = super::create_tracked_async_iterable(&vals.values(), &state);
+
+        let mut stream = JsStream::<JsString>::new(iter)?;
+        assert_eq!(JsString::from("foo"), stream.next().await.unwrap()?);
+
+        drop(stream);
+        JsFuture::from(super::delay()).await?;
+        assert_eq!(Reflect::get(&state, &"finalized".into())?.as_bool(), Some(true));
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_type_cast_error() {
+    async fn run() -> Result<(), Error> {
+        let vals = vec![JsValue::from(42), JsValue::from("foo")];
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+
+        let mut stream = JsStream::<JsString>::new(iter)?;
+        match stream.next().await.unwrap() {
+            Err(JsStreamError::TypeCast { value, .. }) => assert_eq!(value.as_f64(), Some(42.0)),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(JsString::from("foo"), stream.next().await.unwrap()?);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_rust_types() {
+    async fn run() -> Result<(), JsValue> {
+        let vals = vec!["foo", "bar"].into_iter().map(Into::into).collect::<Vec<JsValue>>();
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+        let strings = JsStream::<String>::new(iter)?.try_collect::<Vec<_>>().await?;
+        assert_eq!(strings, ["foo", "bar"]);
+
+        let vals = vec![JsValue::from(1.5), JsValue::NULL, JsValue::UNDEFINED];
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+        let numbers = JsStream::<Option<f64>>::new(iter)?.try_collect::<Vec<_>>().await?;
+        assert_eq!(numbers, [Some(1.5), None, None]);
+
+        let vals = vec![JsValue::from(255), JsValue::from(256), JsValue::from(1.5)];
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+        let bytes = JsStream::<u8>::new(iter)?.collect::<Vec<_>>().await;
+        assert!(matches!(bytes[0], Ok(255)));
+        assert!(matches!(bytes[1], Err(JsStreamError::TypeCast { expected: "u8", .. })));
+        assert!(matches!(bytes[2], Err(JsStreamError::TypeCast { expected: "u8", .. })));
+
+        let vals = std::iter::once(JsValue::from(Uint8Array::from(&[1u8, 2][..]))).collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+        let buffers = JsStream::<Vec<u8>>::new(iter)?.try_collect::<Vec<_>>().await?;
+        assert_eq!(buffers, [vec![1, 2]]);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen]
+extern {
+    // NOTE: declared outside of the crate, so it cannot implement `FromJsValue` there
+    #[wasm_bindgen(js_name = Date)]
+    type ForeignDate;
+}
+
+#[wasm_bindgen_test]
+async fn stream_cast() {
+    async fn run() -> Result<(), JsValue> {
+        let vals = vec![JsValue::from(Date::new_0()), JsValue::from(42)];
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+        let dates = JsStream::<Cast<ForeignDate>>::new(iter)?.collect::<Vec<_>>().await;
+        assert!(matches!(&dates[0], Ok(Cast(date)) if date.is_instance_of::<Date>()));
+        assert!(matches!(dates[1], Err(JsStreamError::TypeCast { .. })));
+
+        let vals = vec![
+            JsValue::from(Uint8Array::new_with_length(2)),
+            ArrayBuffer::new(2).into(),
+        ];
+        let vals = vals.into_iter().collect::<Array>();
+        let iter = super::create_async_iterable(&vals.values());
+        let views = JsStream::<sys::ArrayBufferView>::new(iter)?.collect::<Vec<_>>().await;
+        assert!(matches!(&views[0], Ok(view) if view.byte_length() == 2));
+        assert!(matches!(views[1], Err(JsStreamError::TypeCast { .. })));
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_sync_iterator() {
+    async fn run() -> Result<(), JsStreamError> {
+        let vals = vec![JsValue::from("foo"), Promise::resolve(&"bar".into()).into()];
+        let vals = vals.into_iter().collect::<Array>();
+
+        let stream = JsStream::<String>::from_sync_iterator(vals.values())?;
+        let vals = stream.try_collect::<Vec<_>>().await?;
+        assert_eq!(vec!["foo", "bar"], vals);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_sync_iterator_rejected() {
+    async fn run() -> Result<(), JsStreamError> {
+        let vals = vec![JsValue::from("foo"), Promise::reject(&"failed".into()).into()];
+        let vals = vals.into_iter().collect::<Array>();
+
+        let mut stream = JsStream::<String>::from_sync_iterator(vals.values())?;
+        assert_eq!(Some("foo".to_string()), stream.try_next().await?);
+        let error = stream.try_next().await.unwrap_err();
+        assert_eq!(Some("failed".to_string()), error.message());
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_async_iterable() {
+    async fn run() -> Result<(), JsStreamError> {
+        let vals = vec![JsValue::from("foo"), JsValue::from("bar")];
+        let vals = vals.into_iter().collect::<Array>();
+
+        let iter = super::create_async_iterable(&vals.values());
+        let stream = JsStream::<String>::from_async_iterable(&iter)?;
+        assert_eq!(vec!["foo", "bar"], stream.try_collect::<Vec<_>>().await?);
+
+        let stream = JsStream::<String>::from_async_iterable(&vals)?;
+        assert_eq!(vec!["foo", "bar"], stream.try_collect::<Vec<_>>().await?);
+
+        let stream = JsStream::<String>::from_async_iterable(&"a\u{1F600}".into())?;
+        assert_eq!(vec!["a", "\u{1F600}"], stream.try_collect::<Vec<_>>().await?);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+fn stream_not_async_iterable() {
+    for value in [
+        Object::new().into(),
+        JsValue::NULL,
+        JsValue::UNDEFINED,
+        JsValue::from(42),
+    ] {
+        let error = JsStream::<JsValue>::from_async_iterable(&value).err().unwrap();
+        assert!(error.is_instance_of::<TypeError>());
+    }
+}
+
+#[wasm_bindgen_test]
+async fn stream_fused() {
+    async fn run() -> Result<(), JsStreamError> {
+        let vals = std::iter::once(JsValue::from("foo")).collect::<Array>();
+
+        let mut stream = JsStream::<String>::from_sync_iterator(vals.values())?;
+        assert!(!stream.is_terminated());
+        assert_eq!(Some("foo".to_string()), stream.try_next().await?);
+        assert_eq!(None, stream.try_next().await?);
+        assert!(stream.is_terminated());
+        assert_eq!(None, stream.try_next().await?);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn stream_prefetch() {
+    async fn run() -> Result<(), JsValue> {
+        let state = Object::new();
+        let iter = super::create_concurrent_iterator(5, &state);
+
+        let mut stream = JsStream::<u32>::new(iter)?.with_prefetch(3);
+        assert_eq!(Some(0), stream.try_next().await?);
+        let calls = Reflect::get(&state, &"calls".into())?.as_f64().unwrap();
+        assert!(calls >= 3.0);
+
+        let rest = stream.try_collect::<Vec<_>>().await?;
+        assert_eq!(vec![1, 2, 3, 4], rest);
+
+        Ok(())
+    }
+
+    run().await.unwrap();
+}
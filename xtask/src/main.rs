@@ -341,7 +341,7 @@ FLAGS:
             let mut cmd = Command::new(cargo);
             cmd.current_dir(metadata::project_root());
             cmd.env("RUSTFLAGS", "-Dwarnings");
-            cmd.args(&["test", "--examples", "--lib", "--tests"]);
+            cmd.args(&["test", "--examples", "--lib", "--tests", "--all-features"]);
             cmd.args(&["--package", "xtask"]);
             cmd.args(&["--package", "js-sys-futures"]);
             cmd.args(cargo_args);